@@ -6,8 +6,12 @@
 // Type out about 80 method names? No, thanks
 #![allow(clippy::wildcard_imports)]
 use crate::{
-    download_file, errors,
-    event_loop::EventLoop,
+    download_file::{
+        self, download_by_id, download_file_resumable, download_file_stream,
+        download_file_to, download_file_to_path,
+    },
+    errors,
+    event_loop::{webhook::reply, EventLoop},
     internal::Sealed,
     methods::*,
     state::StatefulEventLoop,
@@ -29,14 +33,37 @@ use crate::{
         InlineMessageId,
     },
 };
-use std::{net::IpAddr, num::NonZeroU32, sync::Arc};
+use futures::Stream;
+use hyper::body::Bytes;
+use std::{net::IpAddr, num::NonZeroU32, path::Path, sync::Arc};
+use tokio::io::AsyncWrite;
 
 mod builder;
+mod connection_pool;
+mod file_id_cache;
+mod file_paths;
 mod inner_bot;
+mod interceptor;
+mod metrics;
+mod rate_limiter;
+mod retry_policy;
 
 pub use builder::Builder;
+pub use download_file::DownloadProgress;
 pub use hyper::Uri;
-pub(crate) use inner_bot::InnerBot;
+pub use {
+    connection_pool::ConnectionPool,
+    file_id_cache::{FileIdStore, MemoryStore},
+    interceptor::{InterceptedCall, Interceptor},
+    rate_limiter::RateLimits,
+    retry_policy::RetryPolicy,
+};
+pub(crate) use {
+    file_paths::FilePaths,
+    inner_bot::InnerBot,
+    metrics::Metrics,
+    rate_limiter::{Chat as RateLimitedChat, RateLimiter},
+};
 
 /// A `Bot` is the entry point to interacting with the Bot API.
 ///
@@ -95,6 +122,7 @@ pub(crate) use inner_bot::InnerBot;
 #[must_use]
 pub struct Bot {
     inner: Arc<InnerBot>,
+    webhook_reply: Option<reply::Slot>,
 }
 
 impl Bot {
@@ -145,7 +173,211 @@ impl Bot {
         &self,
         file: &File,
     ) -> Result<Vec<u8>, errors::Download> {
-        download_file(&self.inner, file).await
+        download_file::download_file(&self.inner, file).await
+    }
+
+    /// Downloads a file by its ID.
+    ///
+    /// Unlike [`download_file`], this method gets the file's path itself,
+    /// calling [`get_file`] only if it hasn't done so for this file during
+    /// the last hour, for which the Bot API guarantees the path to be valid.
+    /// If the download fails with `404 Not Found` nonetheless, the path is
+    /// fetched again and the download is repeated.
+    ///
+    /// If getting the path fails, this method fails with
+    /// [`errors::Download::GetFile`].
+    ///
+    /// [`download_file`]: Self::download_file
+    /// [`get_file`]: Self::get_file
+    pub async fn download_by_id(
+        &self,
+        file_id: file::Id,
+    ) -> Result<Vec<u8>, errors::Download> {
+        download_by_id(&self.inner, &file_id).await
+    }
+
+    /// Downloads a file into `writer`, calling `on_progress` after each
+    /// received chunk. Unlike [`download_file`], this method doesn't keep
+    /// the whole file in memory.
+    ///
+    /// If the downloaded size differs from the file's expected size, fails
+    /// with [`errors::Download::SizeMismatch`].
+    ///
+    /// [`download_file`]: Self::download_file
+    pub async fn download_file_to<W>(
+        &self,
+        file: &File,
+        writer: W,
+        on_progress: impl FnMut(DownloadProgress),
+    ) -> Result<(), errors::Download>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        download_file_to(&self.inner, file, writer, on_progress).await
+    }
+
+    /// Downloads a file to `path`, calling `on_progress` after each received
+    /// chunk. The file is written to a temporary file next to `path` first,
+    /// which replaces `path` only once the download completes, so a failed
+    /// download leaves an existing file at `path` intact.
+    ///
+    /// ```no_run
+    /// # async fn foo(file: tbot::types::File) {
+    /// let bot = tbot::from_env!("BOT_TOKEN");
+    ///
+    /// bot.download_file_to_path(&file, "video.mp4", |progress| {
+    ///     println!("{}/{:?} bytes", progress.downloaded, progress.total);
+    /// })
+    /// .await
+    /// .unwrap();
+    /// # }
+    /// ```
+    pub async fn download_file_to_path(
+        &self,
+        file: &File,
+        path: impl AsRef<Path>,
+        on_progress: impl FnMut(DownloadProgress),
+    ) -> Result<(), errors::Download> {
+        download_file_to_path(&self.inner, file, path.as_ref(), on_progress)
+            .await
+    }
+
+    /// Downloads a file to `path`, resuming the download if it fails midway,
+    /// and calls `on_progress` after each received chunk.
+    ///
+    /// The file is downloaded to a sidecar file named after `path` and the
+    /// file's unique ID, e.g. `video.mp4.<unique_id>.part`, which is renamed
+    /// to `path` once the download completes. If the sidecar already exists,
+    /// e.g. left by a previous call to this method, the download continues
+    /// where it stopped using the HTTP `Range` header. When the download
+    /// fails because of a network error, stalls or is interrupted, it's
+    /// resumed according to `retry_policy`. See [`RetryPolicy`] for
+    /// configuring it; note that network errors, timeouts and interrupted
+    /// transfers are always retried, since repeating a download is safe.
+    /// Once all attempts fail, the sidecar is kept so that you can resume
+    /// the download later.
+    ///
+    /// A download stalls if the server doesn't send any data for longer than
+    /// the bot's [request timeout], or for a minute if it isn't configured.
+    ///
+    /// If the downloaded size differs from `file.size`, fails with
+    /// [`errors::Download::SizeMismatch`].
+    ///
+    /// ```no_run
+    /// # async fn foo(file: tbot::types::File) {
+    /// use tbot::bot::RetryPolicy;
+    ///
+    /// let bot = tbot::from_env!("BOT_TOKEN");
+    ///
+    /// bot.download_file_resumable(&file, "video.mp4", RetryPolicy::new(5), |_| ())
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [request timeout]: Builder::request_timeout
+    pub async fn download_file_resumable(
+        &self,
+        file: &File,
+        path: impl AsRef<Path>,
+        retry_policy: RetryPolicy,
+        on_progress: impl FnMut(DownloadProgress),
+    ) -> Result<(), errors::Download> {
+        download_file_resumable(
+            &self.inner,
+            file,
+            path.as_ref(),
+            retry_policy,
+            on_progress,
+        )
+        .await
+    }
+
+    /// Starts downloading a file and returns a stream of its chunks, calling
+    /// `on_progress` after each received chunk.
+    ///
+    /// If the downloaded size differs from the file's expected size, the last
+    /// item of the stream is [`errors::Download::SizeMismatch`].
+    pub async fn download_file_stream(
+        &self,
+        file: &File,
+        on_progress: impl FnMut(DownloadProgress) + Send + 'static,
+    ) -> Result<
+        impl Stream<Item = Result<Bytes, errors::Download>> + Send + 'static,
+        errors::Download,
+    > {
+        download_file_stream(&self.inner, file, on_progress).await
+    }
+
+    /// Sends a method in the response to the webhook request which delivered
+    /// the current update.
+    ///
+    /// This only works if the webhook is configured with [`allow_replies`]
+    /// and this `Bot` comes from a context of an update received via
+    /// the webhook. Only one method can be sent per update, and it must be sent
+    /// before the reply timeout passes. If the method can't be sent
+    /// in the response, it is returned back to you so that you can call it
+    /// as usual:
+    ///
+    /// ```no_run
+    /// # let mut bot = tbot::from_env!("BOT_TOKEN").event_loop();
+    /// use tbot::prelude::*;
+    ///
+    /// bot.text(|context| async move {
+    ///     let reply = context.send_message_in_reply(&context.text.value);
+    ///
+    ///     if let Err(reply) = context.bot.reply_via_webhook(reply) {
+    ///         let call_result = reply.call().await;
+    ///
+    ///         if let Err(err) = call_result {
+    ///             dbg!(err);
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// Note that the Bot API does not report whether a method sent this way
+    /// succeeded, and you don't get its result.
+    ///
+    /// The bot's [interceptors] may inspect, rewrite or veto the method before
+    /// it's put in the response. If an interceptor vetoes the method, this
+    /// method returns it back, so calling it as usual fails with
+    /// [`errors::MethodCall::Vetoed`]. As the outcome is unknown,
+    /// interceptors' `after_call` isn't called for replies.
+    ///
+    /// Replies also bypass what `tbot` does for usual calls: they don't wait
+    /// for the bot's [rate limiter] and aren't counted by it, they aren't
+    /// recorded in [metrics], they're never retried, and if the chat migrated
+    /// to a supergroup, the migration isn't followed. If you rely on any
+    /// of these, call the method as usual instead.
+    ///
+    /// [interceptors]: crate::bot::Builder::interceptor
+    /// [rate limiter]: crate::bot::Builder::rate_limits
+    /// [metrics]: crate::event_loop::webhook::Service::metrics_on
+    /// [`allow_replies`]: crate::event_loop::Webhook::allow_replies
+    pub fn reply_via_webhook<M>(&self, method: M) -> Result<(), M>
+    where
+        M: WebhookReply,
+    {
+        match &self.webhook_reply {
+            Some(slot) => slot.put(&self.inner, method),
+            None => Err(method),
+        }
+    }
+
+    pub(crate) fn metrics(&self) -> &Metrics {
+        self.inner.metrics()
+    }
+
+    pub(crate) fn has_rate_limits(&self) -> bool {
+        self.inner.rate_limiter().is_some()
+    }
+
+    pub(crate) fn with_webhook_reply(&self, slot: reply::Slot) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            webhook_reply: Some(slot),
+        }
     }
 
     /// Constructs an `EventLoop`.
@@ -516,6 +748,22 @@ impl Bot {
         LeaveChat::new(&self.inner, chat_id)
     }
 
+    /// Logs out from the _cloud_ Bot API server. See
+    /// [`bot::Builder::log_out`] for the caveats.
+    ///
+    /// [`bot::Builder::log_out`]: Builder::log_out
+    pub fn log_out(&self) -> LogOut<'_> {
+        LogOut::new(&self.inner)
+    }
+
+    /// Logs out from a _self-hosted_ Bot API server. See
+    /// [`bot::Builder::close`] for the caveats.
+    ///
+    /// [`bot::Builder::close`]: Builder::close
+    pub fn close(&self) -> Close<'_> {
+        Close::new(&self.inner)
+    }
+
     /// Pins a message in a chat.
     pub fn pin_chat_message(
         &self,
@@ -534,6 +782,13 @@ impl Bot {
         PromoteChatMember::new(&self.inner, chat_id, user_id)
     }
 
+    /// Calls a Bot API method which `tbot` doesn't wrap yet.
+    ///
+    /// See [`RawMethod`] for details.
+    pub fn raw_method(&self, name: impl Into<String>) -> RawMethod<'_> {
+        RawMethod::new(&self.inner, name)
+    }
+
     /// Restricts a chat member.
     pub fn restrict_chat_member(
         &self,
@@ -613,6 +868,18 @@ impl Bot {
         SendDocument::new(&self.inner, chat_id, document)
     }
 
+    /// Sends a file as a photo, a video, an animation, an audio, a voice
+    /// message or a document, depending on its contents and `filename`.
+    /// See [`SendFile`] for details.
+    pub fn send_file(
+        &self,
+        chat_id: impl ImplicitChatId,
+        bytes: impl Into<Vec<u8>>,
+        filename: impl Into<String>,
+    ) -> SendFile<'_> {
+        SendFile::new(&self.inner, chat_id, bytes, filename)
+    }
+
     /// Sends an invoice.
     pub fn send_invoice(
         &self,
@@ -1,12 +1,16 @@
-use super::{Bot, InnerBot};
+use super::{
+    Bot, ConnectionPool, FileIdStore, InnerBot, Interceptor, RateLimits,
+    RetryPolicy,
+};
 use crate::{
-    connectors::Client,
+    connectors::{Identity, Transport},
     errors,
     methods::{Close, DeleteWebhook, LogOut},
-    proxy::Proxy,
+    proxy::{NoProxy, Proxy},
     token::Token,
+    types::chat,
 };
-use std::sync::Arc;
+use std::{future::Future, sync::Arc, time::Duration};
 
 /// A builder for a [`Bot`] with advanced configuration.
 #[derive(Debug)]
@@ -16,7 +20,7 @@ pub struct Builder(InnerBot);
 impl Builder {
     /// Starts constructing a `Bot` with the provided token.
     pub fn with_string_token(token: String) -> Self {
-        Self(InnerBot::new(Token(token), Client::https()))
+        Self(InnerBot::new(Token(token)))
     }
 
     /// Starts constructing a `Bot`, extracting the token from the provided
@@ -35,8 +39,233 @@ impl Builder {
 
     /// Configures a proxy through which all the request will go.
     pub fn proxy(mut self, proxy: impl Into<Proxy>) -> Self {
-        let proxy: Proxy = proxy.into();
-        self.0.set_client(proxy.into());
+        self.0.set_proxy(proxy.into(), None);
+        self
+    }
+
+    /// Configures a proxy from the environment, like most other tools do.
+    ///
+    /// The proxy is read from `HTTPS_PROXY` or `ALL_PROXY` as described
+    /// in [`Proxy::from_env`]. If the Bot API server's host is listed
+    /// in `NO_PROXY` (e.g. `NO_PROXY=localhost` for a self-hosted server
+    /// configured with [`server_uri`]), requests go to the server directly.
+    /// If no valid proxy is configured in the environment, this method does
+    /// nothing.
+    ///
+    /// [`server_uri`]: Self::server_uri
+    pub fn proxy_from_env(mut self) -> Self {
+        if let Some(proxy) = Proxy::from_env() {
+            self.0.set_proxy(proxy, NoProxy::from_env());
+        }
+        self
+    }
+
+    /// Configures `tbot` to trust the PEM-encoded root certificates
+    /// in addition to the system's ones, e.g. if your self-hosted Bot API
+    /// server's certificate is issued by an internal certificate authority.
+    /// The certificates also apply to connections through proxies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `certificates` can't be parsed.
+    pub fn root_certificates(
+        mut self,
+        certificates: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.0.add_root_certificate(certificates.into());
+        self
+    }
+
+    /// Configures the client certificate `tbot` presents to the Bot API server,
+    /// e.g. if your self-hosted Bot API server requires mutual TLS.
+    ///
+    /// `certificate_chain` is the PEM-encoded client certificate followed
+    /// by intermediate certificates, and `private_key` is the certificate's
+    /// PEM-encoded PKCS #8 private key. The identity also applies
+    /// to connections through proxies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the certificate chain or the private key can't be parsed.
+    pub fn client_identity(
+        mut self,
+        certificate_chain: impl Into<Vec<u8>>,
+        private_key: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.0.set_client_identity(Identity {
+            certificate_chain: certificate_chain.into(),
+            private_key: private_key.into(),
+        });
+        self
+    }
+
+    /// Configures how `tbot` makes requests to the Bot API server. See
+    /// [`Transport`] for details.
+    ///
+    /// Once you install a custom transport, the settings made with [`proxy`],
+    /// [`connection_pool`], [`http2_only`], [`root_certificates`]
+    /// and [`client_identity`] no longer apply, as they only configure
+    /// the built-in transport.
+    ///
+    /// [`proxy`]: Self::proxy
+    /// [`connection_pool`]: Self::connection_pool
+    /// [`http2_only`]: Self::http2_only
+    /// [`root_certificates`]: Self::root_certificates
+    /// [`client_identity`]: Self::client_identity
+    pub fn transport(mut self, transport: impl Transport) -> Self {
+        self.0.set_transport(transport);
+        self
+    }
+
+    /// Configures `tbot` to keep connections to the Bot API server open and
+    /// reuse them for next requests. By default, `tbot` opens a new connection
+    /// for each request. See [`ConnectionPool`] for details.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use tbot::bot::{self, ConnectionPool};
+    ///
+    /// let bot = bot::Builder::with_string_token(String::from("TOKEN"))
+    ///     .connection_pool(
+    ///         ConnectionPool::new().idle_timeout(Duration::from_secs(30)),
+    ///     )
+    ///     .build();
+    /// ```
+    pub fn connection_pool(mut self, pool: ConnectionPool) -> Self {
+        self.0.set_connection_pool(pool);
+        self
+    }
+
+    /// Configures `tbot` to talk to the Bot API server over HTTP/2 only.
+    ///
+    /// With HTTP/2, concurrent requests share a single connection instead
+    /// of opening a connection each, which works best together with
+    /// [`connection_pool`]. Make sure the server supports HTTP/2: the cloud
+    /// Bot API server does, but a self-hosted one behind a reverse proxy may
+    /// not, in which case all requests fail.
+    ///
+    /// [`connection_pool`]: Self::connection_pool
+    pub fn http2_only(mut self) -> Self {
+        self.0.set_http2_only();
+        self
+    }
+
+    /// Configures how `tbot` retries method calls which failed, e.g. because
+    /// of flood control. By default, `tbot` does not retry method calls.
+    /// See [`RetryPolicy`] for details.
+    ///
+    /// ```
+    /// use tbot::bot::{self, RetryPolicy};
+    ///
+    /// let bot = bot::Builder::with_string_token(String::from("TOKEN"))
+    ///     .retry_policy(RetryPolicy::new(5).retry_network_errors())
+    ///     .build();
+    /// ```
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.0.set_retry_policy(policy);
+        self
+    }
+
+    /// Configures `tbot` to queue calls to methods which send messages so that
+    /// the bot doesn't exceed the Bot API's limits. By default, `tbot` doesn't
+    /// limit method calls. See [`RateLimits`] for details.
+    ///
+    /// ```
+    /// use tbot::bot::{self, RateLimits};
+    ///
+    /// let bot = bot::Builder::with_string_token(String::from("TOKEN"))
+    ///     .rate_limits(RateLimits::new())
+    ///     .build();
+    /// ```
+    pub fn rate_limits(mut self, limits: RateLimits) -> Self {
+        self.0.set_rate_limits(limits);
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// a method call by default. If the timeout passes, the call fails with
+    /// [`errors::MethodCall::Timeout`]. You can override the timeout for
+    /// a specific call with the method's `timeout` method. By default, `tbot`
+    /// waits for as long as it takes.
+    ///
+    /// The timeout starts once the request's body has been sent, so uploading
+    /// a large file doesn't count towards it.
+    ///
+    /// Note that the polling event loop configures the timeout for
+    /// `getUpdates` with [`Polling::request_timeout`].
+    ///
+    /// [`Polling::request_timeout`]: crate::event_loop::Polling::request_timeout
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.0.set_request_timeout(timeout);
+        self
+    }
+
+    /// Installs an interceptor which hooks into every method call the bot
+    /// makes, e.g. to collect per-method metrics, to log outgoing messages
+    /// or to veto calls. Interceptors are called in the order they were
+    /// installed. See [`Interceptor`] for details.
+    pub fn interceptor(mut self, interceptor: impl Interceptor) -> Self {
+        self.0.add_interceptor(interceptor);
+        self
+    }
+
+    /// Configures `tbot` to remember IDs of uploaded files in `store`, so that
+    /// the same content isn't uploaded again. This applies to files
+    /// constructed with `with_bytes` and sent with `send_*` methods,
+    /// including media groups. See [`FileIdStore`] for details.
+    ///
+    /// ```
+    /// use tbot::bot::{self, MemoryStore};
+    ///
+    /// let bot = bot::Builder::with_string_token(String::from("TOKEN"))
+    ///     .file_id_cache(MemoryStore::new())
+    ///     .build();
+    /// ```
+    pub fn file_id_cache(mut self, store: impl FileIdStore) -> Self {
+        self.0.set_file_id_store(store);
+        self
+    }
+
+    /// Configures `tbot` to follow group-to-supergroup migrations.
+    ///
+    /// When a group is upgraded to a supergroup, the Bot API rejects calls
+    /// to methods with the group's old ID and tells the supergroup's new ID.
+    /// Once you call this method, `tbot` re-issues such calls with the new ID
+    /// transparently. Before that, `tbot` calls `on_migration` with the old
+    /// and the new IDs, so that you can re-key your state from the old ID
+    /// to the new one, e.g. with [`state::Chats::migrate`] and
+    /// [`state::Messages::migrate`]. Note that the Bot API also sends
+    /// a service message about the migration, which you can handle with
+    /// [`EventLoop::migration`].
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use tbot::{bot, state::Chats};
+    ///
+    /// let chats = Arc::new(Mutex::new(Chats::<String>::new()));
+    /// let on_migration_chats = Arc::clone(&chats);
+    ///
+    /// let bot = bot::Builder::with_string_token(String::from("TOKEN"))
+    ///     .follow_migrations(move |from, to| {
+    ///         let chats = Arc::clone(&on_migration_chats);
+    ///         async move {
+    ///             chats.lock().unwrap().migrate(from, to);
+    ///         }
+    ///     })
+    ///     .build();
+    /// ```
+    ///
+    /// [`state::Chats::migrate`]: crate::state::Chats::migrate
+    /// [`state::Messages::migrate`]: crate::state::Messages::migrate
+    /// [`EventLoop::migration`]: crate::EventLoop::migration
+    pub fn follow_migrations<H, F>(mut self, on_migration: H) -> Self
+    where
+        H: (Fn(chat::Id, chat::Id) -> F) + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.0.set_migration_handler(move |from, to| {
+            Box::pin(on_migration(from, to))
+        });
         self
     }
 
@@ -208,6 +437,7 @@ impl Builder {
     pub fn build(self) -> Bot {
         Bot {
             inner: Arc::new(self.0),
+            webhook_reply: None,
         }
     }
 }
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+/// Configures how `tbot` reuses connections to the Bot API server.
+///
+/// By default, `tbot` opens a new connection for each request. If your bot
+/// makes a lot of requests, it may spend a considerable amount of time
+/// on TCP and TLS handshakes. With a connection pool configured using
+/// [`bot::Builder::connection_pool`], `tbot` keeps idle connections open
+/// and reuses them for next requests.
+///
+/// [`bot::Builder::connection_pool`]: crate::bot::Builder::connection_pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct ConnectionPool {
+    pub(crate) idle_timeout: Option<Duration>,
+    pub(crate) max_idle_connections: usize,
+    pub(crate) keep_alive: Option<Duration>,
+}
+
+impl ConnectionPool {
+    /// Constructs a pool which keeps any number of idle connections open for
+    /// `90s`.
+    pub const fn new() -> Self {
+        Self {
+            idle_timeout: Some(Duration::from_secs(90)),
+            max_idle_connections: usize::MAX,
+            keep_alive: None,
+        }
+    }
+
+    /// Configures for how long an idle connection is kept open. `90s`
+    /// by default.
+    pub const fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Configures the pool to keep idle connections open until the server
+    /// closes them.
+    pub const fn no_idle_timeout(mut self) -> Self {
+        self.idle_timeout = None;
+        self
+    }
+
+    /// Configures how many idle connections to the same host the pool may
+    /// keep open. Not limited by default.
+    pub const fn max_idle_connections(mut self, max: usize) -> Self {
+        self.max_idle_connections = max;
+        self
+    }
+
+    /// Enables TCP keep-alive with the provided interval, so that idle
+    /// connections aren't silently dropped by NAT or firewalls. Disabled
+    /// by default.
+    pub const fn keep_alive(mut self, interval: Duration) -> Self {
+        self.keep_alive = Some(interval);
+        self
+    }
+}
+
+impl Default for ConnectionPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
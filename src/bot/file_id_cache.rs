@@ -0,0 +1,218 @@
+use crate::types::{
+    file,
+    input_file::{FileSource, InputFile},
+    message::{Kind, Message},
+};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Write},
+    sync::{Arc, Mutex},
+};
+
+/// Stores IDs of files the bot uploaded, keyed by the files' contents.
+///
+/// When the bot is configured with a store using
+/// [`bot::Builder::file_id_cache`], `tbot` hashes every file constructed
+/// with `with_bytes` before uploading it. If the store already has an ID for
+/// this content, `tbot` sends the file by its ID instead of uploading it
+/// again. After a file is uploaded successfully, `tbot` takes its ID from
+/// the sent message and inserts it into the store.
+///
+/// Keys look like `photo:<hash>`, where the hash is the SHA-256 of the file's
+/// name and contents, so that the same content sent as different kinds of
+/// media is cached separately. Keys are stable across restarts, so implement
+/// this trait on top of a database if you want the cache to persist.
+/// Otherwise, use [`MemoryStore`].
+///
+/// If Telegram rejects a cached ID, e.g. because it was issued to another
+/// bot, `tbot` removes it from the store and uploads the file again.
+///
+/// [`bot::Builder::file_id_cache`]: crate::bot::Builder::file_id_cache
+pub trait FileIdStore: Debug + Send + Sync + 'static {
+    /// Looks up the ID of a file stored under `key`.
+    fn get(&self, key: &str) -> Option<file::Id>;
+
+    /// Stores the ID of an uploaded file under `key`.
+    fn insert(&self, key: String, id: file::Id);
+
+    /// Removes the ID stored under `key` after Telegram rejected it.
+    fn remove(&self, key: &str);
+}
+
+impl<T: FileIdStore> FileIdStore for Arc<T> {
+    fn get(&self, key: &str) -> Option<file::Id> {
+        (**self).get(key)
+    }
+
+    fn insert(&self, key: String, id: file::Id) {
+        (**self).insert(key, id);
+    }
+
+    fn remove(&self, key: &str) {
+        (**self).remove(key);
+    }
+}
+
+/// A file which is looked up in the cache before uploading.
+#[derive(Debug)]
+pub struct CachedFile {
+    pub key: String,
+    /// The file as it was before it was replaced with its cached ID.
+    pub original: Option<InputFile>,
+}
+
+/// Keeps file IDs in memory for as long as the bot runs.
+#[derive(Debug, Default)]
+pub struct MemoryStore(Mutex<HashMap<String, file::Id>>);
+
+impl MemoryStore {
+    /// Constructs an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl FileIdStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<file::Id> {
+        self.0.lock().unwrap().get(key).cloned()
+    }
+
+    fn insert(&self, key: String, id: file::Id) {
+        self.0.lock().unwrap().insert(key, id);
+    }
+
+    fn remove(&self, key: &str) {
+        self.0.lock().unwrap().remove(key);
+    }
+}
+
+/// Computes the key for an in-memory file, or returns `None` if the file
+/// isn't in memory and thus isn't cached.
+pub fn key(kind: &str, file: &InputFile) -> Option<String> {
+    let (filename, bytes) = match file {
+        InputFile::File {
+            filename,
+            source: FileSource::Bytes(bytes),
+        } => (filename, bytes),
+        _ => return None,
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update((filename.len() as u64).to_le_bytes());
+    hasher.update(filename.as_bytes());
+    hasher.update(bytes);
+
+    let key =
+        hasher
+            .finalize()
+            .iter()
+            .fold(format!("{}:", kind), |mut key, byte| {
+                let _ = write!(key, "{:02x}", byte);
+                key
+            });
+
+    Some(key)
+}
+
+/// Extracts the ID of the file the message was sent with.
+pub fn uploaded_file_id(message: &Message) -> Option<&file::Id> {
+    match &message.kind {
+        Kind::Photo { photo, .. } => photo.last().map(|size| &size.file_id),
+        Kind::Audio { audio, .. } => Some(&audio.file_id),
+        Kind::Document { document, .. } => Some(&document.file_id),
+        Kind::Video { video, .. } => Some(&video.file_id),
+        Kind::Animation { animation, .. } => Some(&animation.file_id),
+        Kind::Voice { voice, .. } => Some(&voice.file_id),
+        Kind::VideoNote(video_note) => Some(&video_note.file_id),
+        Kind::Sticker(sticker) => Some(&sticker.file_id),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::InnerBot,
+        connectors::{Transport, TransportError},
+        methods::SendPhoto,
+        token::Token,
+        types::{chat, input_file::Photo},
+    };
+    use futures::future::BoxFuture;
+    use hyper::{Body, Request, Response};
+
+    #[test]
+    fn replaces_uploaded_files_with_ids() {
+        let photo = InputFile::with_bytes("photo.jpg", b"photo".to_vec());
+        let key = key("photo", &photo).unwrap();
+        assert!(key.starts_with("photo:"));
+
+        let store = MemoryStore::new();
+        store.insert(key, file::Id::from("cached"));
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_file_id_store(store);
+
+        let mut document = photo.clone();
+        let cached = bot.use_cached_file_id("document", &mut document);
+        assert!(cached.unwrap().key.starts_with("document:"));
+        assert_eq!(document, photo);
+
+        let mut cached_photo = photo.clone();
+        let cached = bot.use_cached_file_id("photo", &mut cached_photo);
+        assert_eq!(cached.unwrap().original, Some(photo));
+        assert_eq!(cached_photo, InputFile::Id(file::Id::from("cached")));
+    }
+
+    /// Rejects the `stale` file ID and accepts any uploaded photo.
+    #[derive(Debug, Default)]
+    struct RejectsStaleIds(Arc<Mutex<usize>>);
+
+    impl Transport for RejectsStaleIds {
+        fn request(
+            &self,
+            request: Request<Body>,
+        ) -> BoxFuture<'static, Result<Response<Body>, TransportError>>
+        {
+            let requests = Arc::clone(&self.0);
+            Box::pin(async move {
+                let body = hyper::body::to_bytes(request.into_body()).await?;
+                *requests.lock().unwrap() += 1;
+
+                let response = if body.windows(5).any(|x| x == b"stale") {
+                    r#"{"ok":false,"error_code":400,"description":
+                    "Bad Request: wrong file identifier/HTTP URL specified"}"#
+                } else {
+                    r#"{"ok":true,"result":{"message_id":1,"date":0,
+                    "chat":{"id":1,"type":"private","first_name":"John"},
+                    "photo":[{"file_id":"fresh","file_unique_id":"unique",
+                    "width":1,"height":1}]}}"#
+                };
+                Ok(Response::new(Body::from(response)))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn reuploads_files_with_rejected_ids() {
+        let photo = InputFile::with_bytes("photo.jpg", b"photo".to_vec());
+        let key = key("photo", &photo).unwrap();
+
+        let store = Arc::new(MemoryStore::new());
+        store.insert(key.clone(), file::Id::from("stale"));
+        let requests = Arc::default();
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(RejectsStaleIds(Arc::clone(&requests)));
+        bot.set_file_id_store(Arc::clone(&store));
+
+        SendPhoto::new(&bot, chat::Id(1), Photo::new(photo))
+            .call()
+            .await
+            .unwrap();
+
+        assert_eq!(*requests.lock().unwrap(), 2);
+        assert_eq!(store.get(&key), Some(file::Id::from("fresh")));
+    }
+}
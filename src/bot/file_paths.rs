@@ -0,0 +1,34 @@
+use crate::types::file::{self, File};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// For how long the Bot API guarantees a file's path to stay valid.
+const VALID_FOR: Duration = Duration::from_secs(60 * 60);
+
+/// Remembers files returned by `getFile`, so that downloading a file by its
+/// ID doesn't require calling `getFile` each time.
+#[derive(Debug, Default)]
+pub struct FilePaths(Mutex<HashMap<file::Id, (File, Instant)>>);
+
+impl FilePaths {
+    pub fn get(&self, id: &file::Id) -> Option<File> {
+        let paths = self.0.lock().unwrap();
+        paths
+            .get(id)
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < VALID_FOR)
+            .map(|(file, _)| file.clone())
+    }
+
+    pub fn insert(&self, id: file::Id, file: File) {
+        let mut paths = self.0.lock().unwrap();
+        paths.retain(|_, (_, fetched_at)| fetched_at.elapsed() < VALID_FOR);
+        paths.insert(id, (file, Instant::now()));
+    }
+
+    pub fn remove(&self, id: &file::Id) {
+        self.0.lock().unwrap().remove(id);
+    }
+}
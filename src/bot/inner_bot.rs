@@ -1,41 +1,250 @@
-use crate::{connectors::Client, token::Token};
+use super::{
+    file_id_cache::{self, CachedFile},
+    ConnectionPool, FileIdStore, FilePaths, Interceptor, Metrics, RateLimiter,
+    RateLimits, RetryPolicy,
+};
+use crate::{
+    connectors::{self, Client, Transport},
+    errors::{self, RequestErrorKind},
+    proxy::{NoProxy, Proxy},
+    token::Token,
+    types::{chat, input_file::InputFile, Message},
+};
+use futures::future::BoxFuture;
 use hyper::Uri;
+use std::{
+    fmt::{self, Debug, Formatter},
+    time::Duration,
+};
 
 const CLOUD_BOT_API: &str = "https://api.telegram.org/";
 
+type MigrationHandlerFn =
+    dyn Fn(chat::Id, chat::Id) -> BoxFuture<'static, ()> + Send + Sync;
+
+/// Called when `tbot` finds out that a group migrated to a supergroup.
+pub struct MigrationHandler(Box<MigrationHandlerFn>);
+
+impl MigrationHandler {
+    pub async fn call(&self, from: chat::Id, to: chat::Id) {
+        (self.0)(from, to).await;
+    }
+}
+
+impl Debug for MigrationHandler {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("MigrationHandler")
+    }
+}
+
 #[derive(Debug)]
 pub struct InnerBot {
     token: Token,
     client: Client,
+    transport: Option<Box<dyn Transport>>,
+    proxy: Option<Proxy>,
+    no_proxy: Option<NoProxy>,
+    connection: connectors::Config,
     uri: Uri,
+    metrics: Metrics,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<RateLimiter>,
+    migration_handler: Option<MigrationHandler>,
+    request_timeout: Option<Duration>,
+    interceptors: Vec<Box<dyn Interceptor>>,
+    file_id_store: Option<Box<dyn FileIdStore>>,
+    file_paths: FilePaths,
 }
 
 impl InnerBot {
-    pub fn new(token: Token, client: Client) -> Self {
+    pub fn new(token: Token) -> Self {
+        let connection = connectors::Config::default();
+
         Self {
             token,
-            client,
+            client: Client::new(None, &connection),
+            transport: None,
+            proxy: None,
+            no_proxy: None,
+            connection,
             uri: Uri::from_static(CLOUD_BOT_API),
+            metrics: Metrics::default(),
+            retry_policy: None,
+            rate_limiter: None,
+            migration_handler: None,
+            request_timeout: None,
+            interceptors: Vec::new(),
+            file_id_store: None,
+            file_paths: FilePaths::default(),
         }
     }
 
-    pub fn set_client(&mut self, client: Client) {
-        self.client = client;
+    pub fn set_proxy(&mut self, proxy: Proxy, no_proxy: Option<NoProxy>) {
+        self.proxy = Some(proxy);
+        self.no_proxy = no_proxy;
+        self.rebuild_client();
+    }
+
+    pub fn set_connection_pool(&mut self, pool: ConnectionPool) {
+        self.connection.pool = Some(pool);
+        self.rebuild_client();
+    }
+
+    pub fn set_http2_only(&mut self) {
+        self.connection.http2_only = true;
+        self.rebuild_client();
+    }
+
+    pub fn add_root_certificate(&mut self, certificate: Vec<u8>) {
+        self.connection.root_certificates.push(certificate);
+        self.rebuild_client();
+    }
+
+    pub fn set_client_identity(&mut self, identity: connectors::Identity) {
+        self.connection.identity = Some(identity);
+        self.rebuild_client();
+    }
+
+    pub fn set_transport(&mut self, transport: impl Transport) {
+        self.transport = Some(Box::new(transport));
+    }
+
+    pub fn add_interceptor(&mut self, interceptor: impl Interceptor) {
+        self.interceptors.push(Box::new(interceptor));
+    }
+
+    pub fn set_file_id_store(&mut self, store: impl FileIdStore) {
+        self.file_id_store = Some(Box::new(store));
+    }
+
+    fn rebuild_client(&mut self) {
+        let bypasses_proxy = match (&self.no_proxy, self.uri.host()) {
+            (Some(no_proxy), Some(host)) => no_proxy.matches(host),
+            _ => false,
+        };
+        let proxy = self.proxy.as_ref().filter(|_| !bypasses_proxy);
+
+        self.client = Client::new(proxy, &self.connection);
     }
 
     pub fn set_uri(&mut self, uri: Uri) {
         self.uri = uri;
+        if self.no_proxy.is_some() {
+            self.rebuild_client();
+        }
+    }
+
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = Some(policy);
+    }
+
+    pub fn set_rate_limits(&mut self, limits: RateLimits) {
+        self.rate_limiter = Some(RateLimiter::new(limits));
+    }
+
+    pub fn set_migration_handler<H>(&mut self, handler: H)
+    where
+        H: Fn(chat::Id, chat::Id) -> BoxFuture<'static, ()>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.migration_handler = Some(MigrationHandler(Box::new(handler)));
+    }
+
+    pub fn set_request_timeout(&mut self, timeout: Duration) {
+        self.request_timeout = Some(timeout);
     }
 
     pub fn token(&self) -> &str {
         &self.token.0
     }
 
-    pub const fn client(&self) -> &Client {
-        &self.client
+    pub fn transport(&self) -> &dyn Transport {
+        self.transport.as_deref().unwrap_or(&self.client)
     }
 
     pub fn uri(&self) -> Uri {
         self.uri.clone()
     }
+
+    pub fn is_self_hosted(&self) -> bool {
+        self.uri != Uri::from_static(CLOUD_BOT_API)
+    }
+
+    pub const fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    pub const fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    pub const fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    pub const fn migration_handler(&self) -> Option<&MigrationHandler> {
+        self.migration_handler.as_ref()
+    }
+
+    pub const fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
+
+    pub fn interceptors(&self) -> &[Box<dyn Interceptor>] {
+        &self.interceptors
+    }
+
+    pub const fn file_paths(&self) -> &FilePaths {
+        &self.file_paths
+    }
+
+    /// Replaces an in-memory file with its ID if the file has been uploaded
+    /// before. Returns `None` if the file isn't cached at all.
+    pub fn use_cached_file_id(
+        &self,
+        kind: &str,
+        file: &mut InputFile,
+    ) -> Option<CachedFile> {
+        let store = self.file_id_store.as_ref()?;
+        let key = file_id_cache::key(kind, file)?;
+
+        let original = store
+            .get(&key)
+            .map(|id| std::mem::replace(file, InputFile::Id(id)));
+        Some(CachedFile { key, original })
+    }
+
+    /// If the call failed because Telegram rejected the file's cached ID,
+    /// removes the ID from the store and returns the original file
+    /// to upload instead.
+    pub fn reject_cached_file_id(
+        &self,
+        cached: &mut Option<CachedFile>,
+        error: &errors::MethodCall,
+    ) -> Option<InputFile> {
+        if error.request_error_kind() != Some(RequestErrorKind::WrongFileId) {
+            return None;
+        }
+
+        let cached = cached.as_mut()?;
+        let original = cached.original.take()?;
+        if let Some(store) = &self.file_id_store {
+            store.remove(&cached.key);
+        }
+
+        Some(original)
+    }
+
+    pub fn cache_file_id(&self, cached: Option<CachedFile>, message: &Message) {
+        let id = file_id_cache::uploaded_file_id(message);
+        if let (Some(store), Some(cached), Some(id)) =
+            (&self.file_id_store, cached, id)
+        {
+            if cached.original.is_none() {
+                store.insert(cached.key, id.clone());
+            }
+        }
+    }
 }
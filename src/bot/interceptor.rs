@@ -0,0 +1,167 @@
+use crate::{errors, types::parameters::ChatId, RequestBody};
+use std::{fmt::Debug, time::Duration};
+
+/// A method call as seen by an [`Interceptor`] before it's made.
+#[derive(Debug)]
+pub struct InterceptedCall<'a> {
+    pub(crate) method: &'a str,
+    pub(crate) boundary: Option<&'a str>,
+    pub(crate) body: RequestBody,
+    pub(crate) is_rewritten: bool,
+}
+
+impl InterceptedCall<'_> {
+    /// The name of the Bot API method being called, e.g. `sendMessage`.
+    #[must_use]
+    pub const fn method(&self) -> &str {
+        self.method
+    }
+
+    /// Whether the body is `multipart/form-data`. Otherwise, the body is JSON.
+    #[must_use]
+    pub const fn is_multipart(&self) -> bool {
+        self.boundary.is_some()
+    }
+
+    /// The boundary of the `multipart/form-data` body, if the body is
+    /// multipart.
+    #[must_use]
+    pub const fn boundary(&self) -> Option<&str> {
+        self.boundary
+    }
+
+    /// Whether the body contains files which are streamed when the request
+    /// is sent, e.g. files constructed with `with_path`. Such bodies can't be
+    /// replaced, and [`body`] returns an empty slice for them. Their fields
+    /// other than files can still be inspected with [`chat_id`]
+    /// and [`field`].
+    ///
+    /// [`body`]: Self::body
+    /// [`chat_id`]: Self::chat_id
+    /// [`field`]: Self::field
+    #[must_use]
+    pub fn is_streamed(&self) -> bool {
+        self.body.in_memory().is_none()
+    }
+
+    /// The serialized body of the request, or an empty slice if the body
+    /// [is streamed].
+    ///
+    /// [is streamed]: Self::is_streamed
+    #[must_use]
+    pub fn body(&self) -> &[u8] {
+        self.body.in_memory().unwrap_or_default()
+    }
+
+    /// The chat the call is made in, parsed from its `chat_id` parameter.
+    /// Unlike [`body`], this works for streamed bodies too, so prefer it when
+    /// deciding whether to veto a call.
+    ///
+    /// [`body`]: Self::body
+    #[must_use]
+    pub fn chat_id(&self) -> Option<ChatId> {
+        self.body.chat_id(self.boundary)
+    }
+
+    /// The raw value of the `multipart/form-data` field `name`, if the body
+    /// is multipart and has such a field. Values of JSON parameters,
+    /// e.g. `reply_markup`, are serialized JSON. Files are never returned,
+    /// so this works for streamed bodies too.
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&[u8]> {
+        self.body.field(self.boundary?, name)
+    }
+
+    /// Replaces the body of the request. The new body must be in the same
+    /// format as the original one, i.e. JSON or `multipart/form-data` with
+    /// the same boundary. If the new body changes `chat_id`, the call is
+    /// rate limited for the new chat.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the body [is streamed], as replacing it would drop the files
+    /// being streamed.
+    ///
+    /// [is streamed]: Self::is_streamed
+    pub fn set_body(&mut self, body: Vec<u8>) {
+        assert!(
+            !self.is_streamed(),
+            "\n[tbot]: An interceptor tried to replace the body of a `{}` call \
+             whose files are streamed. Check `InterceptedCall::is_streamed` \
+             before calling `set_body`\n",
+            self.method,
+        );
+
+        self.body = body.into();
+        self.is_rewritten = true;
+    }
+}
+
+/// Hooks into every method call the bot makes.
+///
+/// Interceptors are installed with [`bot::Builder::interceptor`] and are
+/// called in the order they were installed. Before a call is made, each
+/// interceptor may inspect or rewrite the request, or veto the call, in which
+/// case the call fails with [`errors::MethodCall::Vetoed`] and isn't sent to
+/// the Bot API. After the call completes, including all retries, each
+/// interceptor learns its outcome and how long it took.
+///
+/// ```
+/// use std::time::Duration;
+/// use tbot::{
+///     bot::{self, InterceptedCall, Interceptor},
+///     errors,
+///     types::{chat, parameters::ChatId},
+/// };
+///
+/// #[derive(Debug)]
+/// struct NeverSendTo(chat::Id);
+///
+/// impl Interceptor for NeverSendTo {
+///     fn before_call(
+///         &self,
+///         call: &mut InterceptedCall<'_>,
+///     ) -> Result<(), String> {
+///         if call.chat_id() == Some(ChatId::Id(self.0)) {
+///             return Err(String::from("the chat is blacklisted"));
+///         }
+///
+///         Ok(())
+///     }
+///
+///     fn after_call(
+///         &self,
+///         method: &str,
+///         outcome: Result<(), &errors::MethodCall>,
+///         duration: Duration,
+///     ) {
+///         println!("{} took {:?}: {:?}", method, duration, outcome);
+///     }
+/// }
+///
+/// let bot = bot::Builder::with_string_token(String::from("TOKEN"))
+///     .interceptor(NeverSendTo(chat::Id(-1_001_234_567_890)))
+///     .build();
+/// ```
+pub trait Interceptor: Debug + Send + Sync + 'static {
+    /// Called before a method call is made. Return `Err` with a reason
+    /// to veto the call.
+    fn before_call(
+        &self,
+        call: &mut InterceptedCall<'_>,
+    ) -> Result<(), String> {
+        let _ = call;
+        Ok(())
+    }
+
+    /// Called after a method call completes, with its outcome and how long
+    /// it took.
+    fn after_call(
+        &self,
+        method: &str,
+        outcome: Result<(), &errors::MethodCall>,
+        duration: Duration,
+    ) {
+        let _ = (method, outcome, duration);
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// Upper bounds of the method call latency histogram's buckets, in seconds.
+const BUCKETS: [f64; 10] =
+    [0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Escapes a label's value for the Prometheus text format.
+struct LabelValue<'a>(&'a str);
+
+impl Display for LabelValue<'_> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        for char in self.0.chars() {
+            match char {
+                '\\' => formatter.write_str("\\\\")?,
+                '"' => formatter.write_str("\\\"")?,
+                '\n' => formatter.write_str("\\n")?,
+                char => formatter.write_char(char)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct MethodStats {
+    calls: u64,
+    errors: u64,
+    buckets: [u64; BUCKETS.len()],
+    duration_sum: f64,
+}
+
+/// Counters exposed on the webhook server's metrics route.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    updates: Mutex<BTreeMap<&'static str, u64>>,
+    parse_failures: AtomicU64,
+    methods: Mutex<BTreeMap<String, MethodStats>>,
+}
+
+impl Metrics {
+    pub fn record_update(&self, kind: &'static str) {
+        *self.updates.lock().unwrap().entry(kind).or_default() += 1;
+    }
+
+    pub fn record_parse_failure(&self) {
+        self.parse_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a method call once it completes, including all its retries,
+    /// so `duration` also includes the time spent waiting between attempts
+    /// and in the rate limiter's queue.
+    pub fn record_method_call(
+        &self,
+        method: &str,
+        duration: Duration,
+        is_ok: bool,
+    ) {
+        let mut methods = self.methods.lock().unwrap();
+        let stats = methods.entry(method.to_owned()).or_default();
+
+        let duration = duration.as_secs_f64();
+        stats.calls += 1;
+        stats.duration_sum += duration;
+        if !is_ok {
+            stats.errors += 1;
+        }
+
+        BUCKETS
+            .iter()
+            .zip(&mut stats.buckets)
+            .filter(|(bound, _)| duration <= **bound)
+            .for_each(|(_, bucket)| *bucket += 1);
+        drop(methods);
+    }
+
+    /// Renders the metrics in the Prometheus text format.
+    pub fn render(&self, running_handlers: usize) -> String {
+        let mut metrics = String::new();
+
+        // writing to a `String` never fails
+        self.write(&mut metrics, running_handlers).unwrap();
+
+        metrics
+    }
+
+    fn write(
+        &self,
+        metrics: &mut String,
+        running_handlers: usize,
+    ) -> fmt::Result {
+        writeln!(metrics, "# HELP tbot_updates_total Received updates.")?;
+        writeln!(metrics, "# TYPE tbot_updates_total counter")?;
+        for (kind, count) in &*self.updates.lock().unwrap() {
+            writeln!(
+                metrics,
+                "tbot_updates_total{{kind=\"{}\"}} {}",
+                LabelValue(kind),
+                count
+            )?;
+        }
+
+        writeln!(
+            metrics,
+            "# HELP tbot_update_parse_failures_total Updates that failed \
+             to parse."
+        )?;
+        writeln!(metrics, "# TYPE tbot_update_parse_failures_total counter")?;
+        writeln!(
+            metrics,
+            "tbot_update_parse_failures_total {}",
+            self.parse_failures.load(Ordering::Relaxed)
+        )?;
+
+        writeln!(
+            metrics,
+            "# HELP tbot_running_handlers Handlers that are currently running."
+        )?;
+        writeln!(metrics, "# TYPE tbot_running_handlers gauge")?;
+        writeln!(metrics, "tbot_running_handlers {}", running_handlers)?;
+
+        let methods = self.methods.lock().unwrap();
+
+        writeln!(
+            metrics,
+            "# HELP tbot_method_calls_total Calls to Bot API methods."
+        )?;
+        writeln!(metrics, "# TYPE tbot_method_calls_total counter")?;
+        for (method, stats) in &*methods {
+            writeln!(
+                metrics,
+                "tbot_method_calls_total{{method=\"{}\"}} {}",
+                LabelValue(method),
+                stats.calls
+            )?;
+        }
+
+        writeln!(
+            metrics,
+            "# HELP tbot_method_call_errors_total Calls to Bot API methods \
+             that failed."
+        )?;
+        writeln!(metrics, "# TYPE tbot_method_call_errors_total counter")?;
+        for (method, stats) in &*methods {
+            writeln!(
+                metrics,
+                "tbot_method_call_errors_total{{method=\"{}\"}} {}",
+                LabelValue(method),
+                stats.errors
+            )?;
+        }
+
+        writeln!(
+            metrics,
+            "# HELP tbot_method_call_duration_seconds Latency of calls to Bot \
+             API methods, including retries."
+        )?;
+        writeln!(
+            metrics,
+            "# TYPE tbot_method_call_duration_seconds histogram"
+        )?;
+        for (method, stats) in &*methods {
+            for (bound, count) in BUCKETS.iter().zip(&stats.buckets) {
+                writeln!(
+                    metrics,
+                    "tbot_method_call_duration_seconds_bucket\
+                     {{method=\"{}\",le=\"{}\"}} {}",
+                    LabelValue(method),
+                    bound,
+                    count
+                )?;
+            }
+            writeln!(
+                metrics,
+                "tbot_method_call_duration_seconds_bucket\
+                 {{method=\"{}\",le=\"+Inf\"}} {}",
+                LabelValue(method),
+                stats.calls
+            )?;
+            writeln!(
+                metrics,
+                "tbot_method_call_duration_seconds_sum{{method=\"{}\"}} {}",
+                LabelValue(method),
+                stats.duration_sum
+            )?;
+            writeln!(
+                metrics,
+                "tbot_method_call_duration_seconds_count{{method=\"{}\"}} {}",
+                LabelValue(method),
+                stats.calls
+            )?;
+        }
+        drop(methods);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_prometheus_metrics() {
+        let metrics = Metrics::default();
+        metrics.record_update("message");
+        metrics.record_update("message");
+        metrics.record_update("poll");
+        metrics.record_parse_failure();
+        metrics.record_method_call(
+            "sendMessage",
+            Duration::from_millis(20),
+            true,
+        );
+        metrics.record_method_call(
+            "sendMessage",
+            Duration::from_secs(3),
+            false,
+        );
+        // raw methods may have any name
+        metrics.record_method_call(
+            "say \"hi\"\\\n",
+            Duration::from_millis(20),
+            true,
+        );
+
+        let rendered = metrics.render(2);
+        let lines: Vec<_> = rendered.lines().collect();
+        let expected = [
+            r#"tbot_updates_total{kind="message"} 2"#,
+            r#"tbot_updates_total{kind="poll"} 1"#,
+            "tbot_update_parse_failures_total 1",
+            "tbot_running_handlers 2",
+            r#"tbot_method_calls_total{method="sendMessage"} 2"#,
+            r#"tbot_method_call_errors_total{method="sendMessage"} 1"#,
+            r#"tbot_method_call_duration_seconds_bucket{method="sendMessage",le="0.01"} 0"#,
+            r#"tbot_method_call_duration_seconds_bucket{method="sendMessage",le="0.025"} 1"#,
+            r#"tbot_method_call_duration_seconds_bucket{method="sendMessage",le="2.5"} 1"#,
+            r#"tbot_method_call_duration_seconds_bucket{method="sendMessage",le="5"} 2"#,
+            r#"tbot_method_call_duration_seconds_bucket{method="sendMessage",le="+Inf"} 2"#,
+            r#"tbot_method_call_duration_seconds_sum{method="sendMessage"} 3.02"#,
+            r#"tbot_method_call_duration_seconds_count{method="sendMessage"} 2"#,
+            r#"tbot_method_calls_total{method="say \"hi\"\\\n"} 1"#,
+        ];
+
+        for line in &expected {
+            assert!(
+                lines.contains(line),
+                "missing `{}` in:\n{}",
+                line,
+                rendered
+            );
+        }
+        assert!(lines
+            .contains(&"# TYPE tbot_method_call_duration_seconds histogram"));
+    }
+}
@@ -0,0 +1,354 @@
+use crate::types::{chat, parameters::ChatId};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    hash::{Hash, Hasher},
+    num::NonZeroU32,
+    sync::Mutex,
+    time::Duration,
+};
+use tokio::time::{sleep_until, Instant};
+use tracing::debug;
+
+/// How many chats the limiter tracks before it forgets idle chats.
+const MIN_CLEANUP_THRESHOLD: usize = 1024;
+
+/// Configures how often the bot may send messages.
+///
+/// The Bot API allows bots to send about 30 messages per second in total,
+/// 1 message per second to the same private chat, and 20 messages per minute
+/// to the same group; if a bot exceeds these limits, the Bot API responds with
+/// `429 Too Many Requests`. Once you configure the limits with
+/// [`bot::Builder::rate_limits`], `tbot` queues calls to methods which send
+/// messages (`send*` except `sendChatAction`, `copyMessage`
+/// and `forwardMessage`) so that they do not exceed the limits. Other
+/// methods are not limited.
+///
+/// `tbot` reports for how long a call waited in the queue through `tracing`
+/// at the `debug` level.
+///
+/// [`bot::Builder::rate_limits`]: crate::bot::Builder::rate_limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct RateLimits {
+    global: Limit,
+    private_chat: Limit,
+    group: Limit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Limit {
+    count: NonZeroU32,
+    period: Duration,
+}
+
+impl Limit {
+    fn new(count: u32, period: Duration) -> Self {
+        Self {
+            count: NonZeroU32::new(count).unwrap(),
+            period,
+        }
+    }
+}
+
+impl RateLimits {
+    /// Constructs the limits that the Bot API imposes: 30 messages per second
+    /// in total, 1 message per second to a private chat and 20 messages
+    /// per minute to a group or a channel.
+    pub fn new() -> Self {
+        Self {
+            global: Limit::new(30, Duration::from_secs(1)),
+            private_chat: Limit::new(1, Duration::from_secs(1)),
+            group: Limit::new(20, Duration::from_secs(60)),
+        }
+    }
+
+    /// Configures how many messages the bot may send in total per `period`.
+    pub const fn global(mut self, count: NonZeroU32, period: Duration) -> Self {
+        self.global = Limit { count, period };
+        self
+    }
+
+    /// Configures how many messages the bot may send to the same private chat
+    /// per `period`.
+    pub const fn private_chat(
+        mut self,
+        count: NonZeroU32,
+        period: Duration,
+    ) -> Self {
+        self.private_chat = Limit { count, period };
+        self
+    }
+
+    /// Configures how many messages the bot may send to the same group or
+    /// channel per `period`.
+    pub const fn group(mut self, count: NonZeroU32, period: Duration) -> Self {
+        self.group = Limit { count, period };
+        self
+    }
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A chat a rate-limited method sends a message to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Chat {
+    Private(chat::Id),
+    Group(chat::Id),
+    Username(u64),
+}
+
+impl From<&ChatId> for Chat {
+    fn from(chat_id: &ChatId) -> Self {
+        match chat_id {
+            ChatId::Id(id) if id.0 > 0 => Self::Private(*id),
+            ChatId::Id(id) => Self::Group(*id),
+            ChatId::Username(username) => {
+                let mut hasher = DefaultHasher::new();
+                username.to_lowercase().hash(&mut hasher);
+                Self::Username(hasher.finish())
+            }
+        }
+    }
+}
+
+/// Times at which messages were or will be sent, in the order of sending.
+#[derive(Debug, Default)]
+struct Window(VecDeque<Instant>);
+
+impl Window {
+    fn forget_before(&mut self, now: Instant, limit: Limit) {
+        while self
+            .0
+            .front()
+            .map_or(false, |sent_at| *sent_at + limit.period <= now)
+        {
+            self.0.pop_front();
+        }
+    }
+
+    /// Reserves the earliest time, not earlier than the last reserved one,
+    /// when another message may be sent.
+    fn reserve(&mut self, now: Instant, limit: Limit) -> Instant {
+        self.forget_before(now, limit);
+
+        let slot = self.next_slot(now, limit);
+        self.0.push_back(slot);
+        slot
+    }
+
+    fn next_slot(&self, now: Instant, limit: Limit) -> Instant {
+        let last = self.0.back().copied().unwrap_or(now).max(now);
+        let count = limit.count.get() as usize;
+
+        if self.0.len() < count {
+            return last;
+        }
+
+        let oldest = self.0[self.0.len() - count];
+        last.max(oldest + limit.period)
+    }
+
+    /// Releases a reserved time if the message won't be sent after all.
+    fn release(&mut self, slot: Instant) {
+        if let Some(index) =
+            self.0.iter().rposition(|reserved| *reserved == slot)
+        {
+            self.0.remove(index);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Queues {
+    global: Window,
+    chats: HashMap<Chat, Window>,
+    cleanup_threshold: usize,
+}
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    limits: RateLimits,
+    queues: Mutex<Queues>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            queues: Mutex::new(Queues {
+                cleanup_threshold: MIN_CLEANUP_THRESHOLD,
+                ..Queues::default()
+            }),
+        }
+    }
+
+    const fn chat_limit(&self, chat: Chat) -> Limit {
+        match chat {
+            Chat::Private(_) => self.limits.private_chat,
+            Chat::Group(_) | Chat::Username(_) => self.limits.group,
+        }
+    }
+
+    /// Waits until a message may be sent to `chat`. First, the call waits
+    /// in the chat's queue, and only then in the global queue, so that calls
+    /// waiting for a busy chat don't hold up calls to other chats.
+    ///
+    /// If the returned future is dropped before it completes, e.g. because
+    /// the call was cancelled or timed out, its reservations are released.
+    pub async fn wait(&self, chat: Chat) {
+        let started_at = Instant::now();
+
+        let slot = self.reserve_in_chat(chat, started_at);
+        let mut reservation = Reservation {
+            limiter: self,
+            chat,
+            chat_slot: slot,
+            global_slot: None,
+            is_complete: false,
+        };
+        sleep_until(slot).await;
+
+        let slot = self.reserve_globally(Instant::now());
+        reservation.global_slot = Some(slot);
+        sleep_until(slot).await;
+        reservation.is_complete = true;
+
+        let wait = started_at.elapsed();
+        if wait >= Duration::from_millis(1) {
+            debug!(?chat, ?wait, "Waited in the rate limiter's queue");
+        }
+    }
+
+    fn reserve_in_chat(&self, chat: Chat, now: Instant) -> Instant {
+        let limit = self.chat_limit(chat);
+        let mut guard = self.queues.lock().unwrap();
+        let queues = &mut *guard;
+
+        if queues.chats.len() > queues.cleanup_threshold {
+            queues.chats.retain(|chat, window| {
+                window.forget_before(now, self.chat_limit(*chat));
+                !window.0.is_empty()
+            });
+            queues.cleanup_threshold =
+                MIN_CLEANUP_THRESHOLD.max(queues.chats.len() * 2);
+        }
+
+        let slot = queues.chats.entry(chat).or_default().reserve(now, limit);
+        drop(guard);
+
+        slot
+    }
+
+    fn reserve_globally(&self, now: Instant) -> Instant {
+        self.queues
+            .lock()
+            .unwrap()
+            .global
+            .reserve(now, self.limits.global)
+    }
+
+    fn release(
+        &self,
+        chat: Chat,
+        chat_slot: Instant,
+        global_slot: Option<Instant>,
+    ) {
+        let mut queues = self.queues.lock().unwrap();
+
+        if let Some(window) = queues.chats.get_mut(&chat) {
+            window.release(chat_slot);
+        }
+        if let Some(slot) = global_slot {
+            queues.global.release(slot);
+        }
+    }
+}
+
+/// Slots reserved for a message which hasn't been sent yet.
+struct Reservation<'a> {
+    limiter: &'a RateLimiter,
+    chat: Chat,
+    chat_slot: Instant,
+    global_slot: Option<Instant>,
+    /// Whether the message is going to be sent, so the slots stay reserved.
+    is_complete: bool,
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if !self.is_complete {
+            self.limiter
+                .release(self.chat, self.chat_slot, self.global_slot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slides_the_window() {
+        let limit = Limit::new(2, Duration::from_secs(1));
+        let second = Duration::from_secs(1);
+        let now = Instant::now();
+        let mut window = Window::default();
+
+        assert_eq!(window.reserve(now, limit), now);
+        assert_eq!(window.reserve(now, limit), now);
+        assert_eq!(window.reserve(now, limit), now + second);
+        assert_eq!(window.reserve(now, limit), now + second);
+        assert_eq!(window.reserve(now, limit), now + second * 2);
+
+        let later = now + second * 3 / 2;
+        assert_eq!(window.reserve(later, limit), now + second * 2);
+        assert_eq!(
+            window.0,
+            [
+                now + second,
+                now + second,
+                now + second * 2,
+                now + second * 2
+            ]
+        );
+
+        let idle = now + second * 10;
+        assert_eq!(window.reserve(idle, limit), idle);
+        assert_eq!(window.0, [idle]);
+    }
+
+    #[test]
+    fn releases_slots() {
+        let limit = Limit::new(1, Duration::from_secs(1));
+        let now = Instant::now();
+        let mut window = Window::default();
+
+        window.reserve(now, limit);
+        let slot = window.reserve(now, limit);
+        window.release(slot);
+        assert_eq!(window.reserve(now, limit), slot);
+    }
+
+    #[tokio::test]
+    async fn releases_cancelled_reservations() {
+        let hour = Duration::from_secs(3600);
+        let limits =
+            RateLimits::new().private_chat(NonZeroU32::new(1).unwrap(), hour);
+        let limiter = RateLimiter::new(limits);
+        let chat = Chat::Private(chat::Id(1));
+
+        limiter.wait(chat).await;
+        let wait = limiter.wait(chat);
+        let result =
+            tokio::time::timeout(Duration::from_millis(10), wait).await;
+        assert!(result.is_err());
+
+        let queues = limiter.queues.lock().unwrap();
+        assert_eq!(queues.chats[&chat].0.len(), 1);
+        assert_eq!(queues.global.0.len(), 1);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::errors;
+use std::time::Duration;
+
+/// Configures how `tbot` retries method calls which failed.
+///
+/// By default, `tbot` does not retry method calls. Once you configure
+/// a policy with [`bot::Builder::retry_policy`], it applies to all methods
+/// called by the bot, unless you opt out for a specific call using
+/// the method's `without_retries` method.
+///
+/// Note that retrying on network errors may result in a method being executed
+/// twice, e.g. if the connection was closed after the Bot API server had
+/// received the request but before it responded. Keep this in mind
+/// if sending a message twice is worse than not sending it at all.
+///
+/// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    honor_retry_after: bool,
+    max_retry_after: Duration,
+    retry_network_errors: bool,
+    retry_out_of_service: bool,
+    backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Constructs a policy which makes at most `max_attempts` attempts to call
+    /// a method, including the first one. By default, the policy only retries
+    /// calls which failed because of flood control, waiting for as long as
+    /// the Bot API asks in `retry_after`.
+    pub const fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            honor_retry_after: true,
+            max_retry_after: Duration::from_secs(60),
+            retry_network_errors: false,
+            retry_out_of_service: false,
+            backoff: Duration::from_millis(500),
+        }
+    }
+
+    /// Configures the policy not to retry calls which failed because of flood
+    /// control.
+    pub const fn ignore_retry_after(mut self) -> Self {
+        self.honor_retry_after = false;
+        self
+    }
+
+    /// Configures for how long `tbot` may wait when the Bot API asks to retry
+    /// later. If `retry_after` is longer, `tbot` returns the error instead of
+    /// waiting. `60s` by default.
+    pub const fn max_retry_after(mut self, max: Duration) -> Self {
+        self.max_retry_after = max;
+        self
+    }
+
+    /// Configures the policy to retry calls which failed because
+    /// of a network or transport error or timed out.
+    pub const fn retry_network_errors(mut self) -> Self {
+        self.retry_network_errors = true;
+        self
+    }
+
+    /// Configures the policy to retry calls which failed because the Bot API
+    /// was out of service.
+    pub const fn retry_out_of_service(mut self) -> Self {
+        self.retry_out_of_service = true;
+        self
+    }
+
+    /// Configures the delay before retrying a call after a network error or
+    /// if the Bot API was out of service. The delay doubles after each
+    /// attempt. `500ms` by default.
+    pub const fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Decides if a call which failed with `error` on `attempt` (starting
+    /// with 1) should be retried, and for how long to wait before that.
+    pub(crate) fn retry_delay(
+        &self,
+        attempt: u32,
+        error: &errors::MethodCall,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        match error {
+            errors::MethodCall::RequestError {
+                retry_after: Some(retry_after),
+                ..
+            } if self.honor_retry_after => {
+                let retry_after = Duration::from_secs(*retry_after);
+                Some(retry_after).filter(|x| *x <= self.max_retry_after)
+            }
+            errors::MethodCall::Network(_)
+            | errors::MethodCall::Transport(_)
+            | errors::MethodCall::Timeout
+                if self.retry_network_errors =>
+            {
+                Some(self.exponential_backoff(attempt))
+            }
+            errors::MethodCall::OutOfService if self.retry_out_of_service => {
+                Some(self.exponential_backoff(attempt))
+            }
+            _ => None,
+        }
+    }
+
+    /// Decides if a download which failed with `error` on `attempt` should be
+    /// resumed, and for how long to wait before that. Unlike method calls,
+    /// downloads are safe to repeat, so network errors, timeouts and
+    /// interrupted transfers are always retried.
+    pub(crate) fn download_retry_delay(
+        &self,
+        attempt: u32,
+        error: &errors::Download,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        match error {
+            errors::Download::Network(_)
+            | errors::Download::Transport(_)
+            | errors::Download::Timeout => {
+                Some(self.exponential_backoff(attempt))
+            }
+            errors::Download::SizeMismatch { expected, received }
+                if received < expected =>
+            {
+                Some(self.exponential_backoff(attempt))
+            }
+            errors::Download::InvalidStatusCode(status)
+                if status.is_server_error() && self.retry_out_of_service =>
+            {
+                Some(self.exponential_backoff(attempt))
+            }
+            _ => None,
+        }
+    }
+
+    fn exponential_backoff(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        self.backoff
+            .checked_mul(factor)
+            .unwrap_or_else(|| Duration::from_secs(u64::MAX))
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Constructs a policy which makes at most 3 attempts.
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::StatusCode;
+
+    fn flood_control(retry_after: u64) -> errors::MethodCall {
+        errors::MethodCall::RequestError {
+            description: String::from("Too Many Requests"),
+            error_code: 429,
+            migrate_to_chat_id: None,
+            retry_after: Some(retry_after),
+        }
+    }
+
+    #[test]
+    fn doubles_backoff_until_the_last_attempt() {
+        let policy = RetryPolicy::new(4)
+            .retry_network_errors()
+            .retry_out_of_service()
+            .backoff(Duration::from_millis(100));
+        let cases = vec![
+            (1, Some(Duration::from_millis(100))),
+            (2, Some(Duration::from_millis(200))),
+            (3, Some(Duration::from_millis(400))),
+            (4, None),
+            (5, None),
+        ];
+
+        for (attempt, delay) in cases {
+            let timeout = errors::MethodCall::Timeout;
+            let out_of_service = errors::MethodCall::OutOfService;
+            let download_timeout = errors::Download::Timeout;
+            let server_error =
+                errors::Download::InvalidStatusCode(StatusCode::BAD_GATEWAY);
+
+            assert_eq!(policy.retry_delay(attempt, &timeout), delay);
+            assert_eq!(policy.retry_delay(attempt, &out_of_service), delay);
+            assert_eq!(
+                policy.download_retry_delay(attempt, &download_timeout),
+                delay,
+            );
+            assert_eq!(
+                policy.download_retry_delay(attempt, &server_error),
+                delay,
+            );
+        }
+    }
+
+    #[test]
+    fn retries_only_configured_errors() {
+        let policy = RetryPolicy::new(3);
+        let cases = vec![
+            (errors::MethodCall::Timeout, None),
+            (errors::MethodCall::OutOfService, None),
+            (errors::MethodCall::Vetoed(String::new()), None),
+            (flood_control(5), Some(Duration::from_secs(5))),
+        ];
+
+        for (error, delay) in cases {
+            assert_eq!(policy.retry_delay(1, &error), delay);
+        }
+
+        let download_cases = vec![
+            (errors::Download::Timeout, true),
+            (
+                errors::Download::SizeMismatch {
+                    expected: 10,
+                    received: 5,
+                },
+                true,
+            ),
+            (
+                errors::Download::SizeMismatch {
+                    expected: 5,
+                    received: 10,
+                },
+                false,
+            ),
+            (
+                errors::Download::InvalidStatusCode(StatusCode::BAD_GATEWAY),
+                false,
+            ),
+            (
+                errors::Download::InvalidStatusCode(StatusCode::NOT_FOUND),
+                false,
+            ),
+            (errors::Download::NoPath, false),
+        ];
+
+        for (error, is_retried) in download_cases {
+            assert_eq!(
+                policy.download_retry_delay(1, &error).is_some(),
+                is_retried,
+            );
+        }
+    }
+
+    #[test]
+    fn honors_retry_after() {
+        let policy =
+            RetryPolicy::new(3).max_retry_after(Duration::from_secs(10));
+        let cases = vec![
+            (1, Some(Duration::from_secs(1))),
+            (10, Some(Duration::from_secs(10))),
+            (11, None),
+        ];
+
+        for (retry_after, delay) in cases {
+            let error = flood_control(retry_after);
+            assert_eq!(policy.retry_delay(1, &error), delay);
+            assert_eq!(policy.retry_delay(3, &error), None);
+        }
+
+        let ignoring = policy.ignore_retry_after();
+        assert_eq!(ignoring.retry_delay(1, &flood_control(1)), None);
+    }
+}
@@ -1,15 +1,34 @@
 //! A few common connectors for making requests.
+//!
+//! By default, `tbot` makes requests to the Bot API with one of the clients
+//! from this module, depending on the configured proxy. If you need full
+//! control over how requests are made (e.g. to resolve hosts in a custom way,
+//! to sign requests or to record them in tests), implement [`Transport`]
+//! and install it with [`bot::Builder::transport`].
+//!
+//! [`bot::Builder::transport`]: crate::bot::Builder::transport
 
+use crate::{bot::ConnectionPool, proxy::Proxy};
+use futures::future::BoxFuture;
 use hyper::{
     self,
-    client::{HttpConnector, ResponseFuture},
-    Body, Request, Uri,
+    client::{connect::Connect, HttpConnector},
+    Body, Request, Response, Uri,
 };
+use std::{error::Error, fmt::Debug};
 
 #[cfg(feature = "rustls")]
 use hyper_rustls::HttpsConnector;
 #[cfg(feature = "tls")]
 use hyper_tls::HttpsConnector;
+#[cfg(feature = "rustls")]
+use std::sync::Arc;
+
+/// TLS settings shared by all connectors.
+#[cfg(feature = "rustls")]
+type Tls = Arc<tokio_rustls::rustls::ClientConfig>;
+#[cfg(feature = "tls")]
+type Tls = native_tls::TlsConnector;
 
 use hyper_proxy as proxy;
 use proxy::ProxyConnector;
@@ -25,86 +44,306 @@ pub type HttpsProxy = ProxyConnector<Https>;
 /// The default SOCKS proxy connector.
 pub type SocksProxy = HttpsConnector<SocksConnector<Https>>;
 
-#[derive(Debug)]
-pub enum Client {
-    Https(hyper::Client<Https>),
-    HttpsProxy(hyper::Client<HttpsProxy>),
-    SocksProxy(hyper::Client<SocksProxy>),
+/// The error a [`Transport`] fails with. `hyper`'s errors are converted into
+/// it, and custom transports can report their own failures.
+pub type TransportError = Box<dyn Error + Send + Sync>;
+
+/// Makes HTTP requests to the Bot API server on behalf of the bot.
+///
+/// Both method calls and file downloads go through the transport.
+/// The transport receives a complete request with an absolute URI and
+/// resolves to the server's response, or fails with an error. `tbot` reports
+/// `hyper`'s errors as [`errors::MethodCall::Network`] or
+/// [`errors::Download::Network`], and other errors as
+/// [`errors::MethodCall::Transport`] or [`errors::Download::Transport`].
+/// `tbot` implements this trait for
+/// [`hyper::Client`], so you can also configure a `hyper` client with your
+/// own connector and install it as is.
+///
+/// ```
+/// use hyper::{Body, Request, Response};
+/// use tbot::{
+///     bot,
+///     connectors::{Transport, TransportError},
+/// };
+///
+/// #[derive(Debug)]
+/// struct AlwaysOk;
+///
+/// impl Transport for AlwaysOk {
+///     fn request(
+///         &self,
+///         _: Request<Body>,
+///     ) -> futures::future::BoxFuture<
+///         'static,
+///         Result<Response<Body>, TransportError>,
+///     > {
+///         let body = Body::from(r#"{"ok":true,"result":true}"#);
+///         Box::pin(async move { Ok(Response::new(body)) })
+///     }
+/// }
+///
+/// let bot = bot::Builder::with_string_token(String::from("TOKEN"))
+///     .transport(AlwaysOk)
+///     .build();
+/// ```
+pub trait Transport: Debug + Send + Sync + 'static {
+    /// Sends the request and resolves to the server's response.
+    fn request(
+        &self,
+        request: Request<Body>,
+    ) -> BoxFuture<'static, Result<Response<Body>, TransportError>>;
 }
 
-impl Client {
-    pub(crate) fn https_proxy(proxy: proxy::Proxy) -> Self {
+impl<C> Transport for hyper::Client<C>
+where
+    C: Connect + Clone + Debug + Send + Sync + 'static,
+{
+    fn request(
+        &self,
+        request: Request<Body>,
+    ) -> BoxFuture<'static, Result<Response<Body>, TransportError>> {
+        let response = Self::request(self, request);
+        Box::pin(async move { response.await.map_err(Into::into) })
+    }
+}
+
+/// A client certificate chain and its private key, PEM-encoded.
+#[derive(Debug, Clone)]
+pub(crate) struct Identity {
+    pub certificate_chain: Vec<u8>,
+    pub private_key: Vec<u8>,
+}
+
+/// Configures connections made by a [`Client`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Config {
+    pub pool: Option<ConnectionPool>,
+    pub http2_only: bool,
+    /// PEM-encoded certificates to trust in addition to the system's ones.
+    pub root_certificates: Vec<Vec<u8>>,
+    pub identity: Option<Identity>,
+}
+
+impl Config {
+    fn http_connector(&self) -> HttpConnector {
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+        http.set_keepalive(self.pool.and_then(|pool| pool.keep_alive));
+        http
+    }
+
+    fn https_connector(&self, tls: Tls) -> Https {
+        #[cfg(feature = "rustls")]
+        {
+            HttpsConnector::from((self.http_connector(), tls))
+        }
+
+        #[cfg(feature = "tls")]
+        {
+            HttpsConnector::from((self.http_connector(), tls.into()))
+        }
+    }
+
+    /// Configures TLS with the configured certificates. Connections to
+    /// a proxy itself don't negotiate HTTP/2, as tunnels are only
+    /// established over HTTP/1.1.
+    fn tls(&self, http2_only: bool) -> Tls {
         #[cfg(feature = "rustls")]
-        let https_connector = HttpsConnector::with_native_roots();
+        {
+            use tokio_rustls::rustls::{internal::pemfile, ClientConfig};
+
+            let mut tls = ClientConfig::new();
+            tls.root_store = match rustls_native_certs::load_native_certs() {
+                Ok(store) | Err((Some(store), _)) => store,
+                Err((None, error)) => panic!(
+                    "[tbot] Failed to load native certificates: {:#?}",
+                    error
+                ),
+            };
+
+            for certificate in &self.root_certificates {
+                match tls.root_store.add_pem_file(&mut &certificate[..]) {
+                    Ok((added, 0)) if added > 0 => (),
+                    _ => panic!("[tbot] Failed to parse a root certificate"),
+                }
+            }
+
+            if let Some(identity) = &self.identity {
+                let chain =
+                    pemfile::certs(&mut &identity.certificate_chain[..])
+                        .ok()
+                        .filter(|chain| !chain.is_empty())
+                        .unwrap_or_else(|| {
+                            panic!(
+                                "[tbot] Failed to parse the client \
+                                 certificate chain"
+                            )
+                        });
+                let key = pemfile::pkcs8_private_keys(
+                    &mut &identity.private_key[..],
+                )
+                .ok()
+                .and_then(|mut keys| keys.pop())
+                .unwrap_or_else(|| {
+                    panic!("[tbot] Failed to parse the client's private key")
+                });
+
+                tls.set_single_client_cert(chain, key).unwrap_or_else(
+                    |error| {
+                        panic!(
+                            "[tbot] Failed to configure the client \
+                             certificate: {:#?}",
+                            error
+                        )
+                    },
+                );
+            }
+
+            tls.alpn_protocols = if http2_only {
+                vec![b"h2".to_vec()]
+            } else {
+                vec![b"http/1.1".to_vec()]
+            };
+
+            Arc::new(tls)
+        }
+
         #[cfg(feature = "tls")]
-        let https_connector = HttpsConnector::new();
-        let connector = ProxyConnector::from_proxy(https_connector, proxy)
-            .unwrap_or_else(|error| {
+        {
+            use native_tls::{Certificate, Identity, TlsConnector};
+
+            let mut tls = TlsConnector::builder();
+            if http2_only {
+                tls.request_alpns(&["h2"]);
+            }
+
+            for certificates in &self.root_certificates {
+                let certificates = Certificate::stack_from_pem(certificates)
+                    .unwrap_or_else(|error| {
+                        panic!(
+                            "[tbot] Failed to parse a root certificate: {:#?}",
+                            error
+                        )
+                    });
+                for certificate in certificates {
+                    tls.add_root_certificate(certificate);
+                }
+            }
+
+            if let Some(identity) = &self.identity {
+                let identity = Identity::from_pkcs8(
+                    &identity.certificate_chain,
+                    &identity.private_key,
+                )
+                .unwrap_or_else(|error| {
+                    panic!(
+                        "[tbot] Failed to parse the client identity: {:#?}",
+                        error
+                    )
+                });
+                tls.identity(identity);
+            }
+
+            tls.build().unwrap_or_else(|error| {
                 panic!(
-                    "[tbot] Failed to construct a proxy connector: {:#?}",
+                    "[tbot] Failed to construct a TLS connector: {:#?}",
                     error
                 )
-            });
+            })
+        }
+    }
+
+    fn client<C>(&self, connector: C) -> hyper::Client<C>
+    where
+        C: Connect + Clone,
+    {
+        let mut builder = hyper::Client::builder();
+
+        match self.pool {
+            Some(pool) => builder
+                .pool_idle_timeout(pool.idle_timeout)
+                .pool_max_idle_per_host(pool.max_idle_connections),
+            None => builder.pool_max_idle_per_host(0),
+        };
+
+        builder.http2_only(self.http2_only).build(connector)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum Client {
+    Https(hyper::Client<Https>),
+    HttpsProxy(hyper::Client<HttpsProxy>),
+    SocksProxy(hyper::Client<SocksProxy>),
+}
 
-        Self::HttpsProxy(
-            hyper::Client::builder()
-                .pool_max_idle_per_host(0)
-                .build::<HttpsProxy, Body>(connector),
-        )
+impl Client {
+    pub(crate) fn new(proxy: Option<&Proxy>, config: &Config) -> Self {
+        match proxy {
+            Some(Proxy::Https(https)) => {
+                Self::https_proxy(https.clone(), config)
+            }
+            Some(Proxy::Socks { uri, auth }) => {
+                Self::socks_proxy(uri.clone(), auth.clone(), config)
+            }
+            None => Self::https(config),
+        }
     }
 
-    pub(crate) fn socks_proxy(proxy_addr: Uri, auth: Option<Auth>) -> Self {
+    pub(crate) fn https_proxy(proxy: proxy::Proxy, config: &Config) -> Self {
+        let to_proxy = config.https_connector(config.tls(false));
+        let mut connector =
+            ProxyConnector::from_proxy_unsecured(to_proxy, proxy);
+
         #[cfg(feature = "rustls")]
-        let https_connector = HttpsConnector::with_native_roots();
+        let tls =
+            tokio_rustls::TlsConnector::from(config.tls(config.http2_only));
         #[cfg(feature = "tls")]
-        let https_connector = HttpsConnector::new();
+        let tls = config.tls(config.http2_only);
+        connector.set_tls(Some(tls));
+
+        Self::HttpsProxy(config.client(connector))
+    }
+
+    pub(crate) fn socks_proxy(
+        proxy_addr: Uri,
+        auth: Option<Auth>,
+        config: &Config,
+    ) -> Self {
         let connector = SocksConnector {
             proxy_addr,
             auth,
-            connector: https_connector,
+            connector: config.https_connector(config.tls(false)),
         };
+        let tls = config.tls(config.http2_only);
 
-        let connector = connector.with_tls().unwrap_or_else(|error| {
-            panic!(
-                "[tbot] Failed to construct a SOCKS proxy connector: {:#?}",
-                error
-            )
-        });
-
-        Self::SocksProxy(
-            hyper::Client::builder()
-                .pool_max_idle_per_host(0)
-                .build::<SocksProxy, Body>(connector),
-        )
-    }
-
-    #[must_use]
-    pub(crate) fn https() -> Self {
         #[cfg(feature = "rustls")]
-        let connector = HttpsConnector::with_native_roots();
+        let connector = HttpsConnector::from((connector, tls));
         #[cfg(feature = "tls")]
-        let connector = HttpsConnector::new();
+        let connector = HttpsConnector::from((connector, tls.into()));
 
-        Self::Https(
-            hyper::Client::builder()
-                .pool_max_idle_per_host(0)
-                .build::<Https, Body>(connector),
-        )
+        Self::SocksProxy(config.client(connector))
     }
 
-    pub(crate) fn get(&self, uri: Uri) -> ResponseFuture {
-        match self {
-            Self::Https(https) => https.get(uri),
-            Self::HttpsProxy(proxy) => proxy.get(uri),
-            Self::SocksProxy(socks_proxy) => socks_proxy.get(uri),
-        }
+    #[must_use]
+    pub(crate) fn https(config: &Config) -> Self {
+        let tls = config.tls(config.http2_only);
+        Self::Https(config.client(config.https_connector(tls)))
     }
+}
 
-    pub(crate) fn request(&self, req: Request<Body>) -> ResponseFuture {
+impl Transport for Client {
+    fn request(
+        &self,
+        request: Request<Body>,
+    ) -> BoxFuture<'static, Result<Response<Body>, TransportError>> {
         match self {
-            Self::Https(https) => https.request(req),
-            Self::HttpsProxy(proxy) => proxy.request(req),
-            Self::SocksProxy(socks_proxy) => socks_proxy.request(req),
+            Self::Https(https) => Transport::request(https, request),
+            Self::HttpsProxy(proxy) => Transport::request(proxy, request),
+            Self::SocksProxy(socks_proxy) => {
+                Transport::request(socks_proxy, request)
+            }
         }
     }
 }
@@ -49,7 +49,10 @@ mod texts;
 
 pub use {
     album::Album,
-    attachments::{Animation, Audio, Document, Location, Photo, Video},
+    attachments::{
+        Animation, Audio, Document, Location, Photo, Sticker, Video, VideoNote,
+        Voice,
+    },
     callback::Callback,
     context::Context,
     messages::{EditedMessage, Forward, MediaMessage, Message},
@@ -1,22 +1,52 @@
 use super::MediaMessage;
-use crate::types::{self, PhotoSize};
+use crate::{
+    errors,
+    types::{self, PhotoSize},
+};
+use futures::future::BoxFuture;
+
+type Download<'a> = BoxFuture<'a, Result<Vec<u8>, errors::Download>>;
 
 /// A general trait for animation messages.
 pub trait Animation: MediaMessage {
     /// The animation of the message.
     fn animation(&self) -> &types::Animation;
+
+    /// Downloads the animation. See [`Bot::download_by_id`] for details.
+    ///
+    /// [`Bot::download_by_id`]: crate::Bot::download_by_id
+    fn download_animation(&self) -> Download<'_> {
+        let file_id = self.animation().file_id.clone();
+        Box::pin(self.bot().download_by_id(file_id))
+    }
 }
 
 /// A general trait for audio messages.
 pub trait Audio: MediaMessage {
     /// The audio of the message.
     fn audio(&self) -> &types::Audio;
+
+    /// Downloads the audio. See [`Bot::download_by_id`] for details.
+    ///
+    /// [`Bot::download_by_id`]: crate::Bot::download_by_id
+    fn download_audio(&self) -> Download<'_> {
+        let file_id = self.audio().file_id.clone();
+        Box::pin(self.bot().download_by_id(file_id))
+    }
 }
 
 /// A general trait for document messages.
 pub trait Document: MediaMessage {
     /// The document of the message.
     fn document(&self) -> &types::Document;
+
+    /// Downloads the document. See [`Bot::download_by_id`] for details.
+    ///
+    /// [`Bot::download_by_id`]: crate::Bot::download_by_id
+    fn download_document(&self) -> Download<'_> {
+        let file_id = self.document().file_id.clone();
+        Box::pin(self.bot().download_by_id(file_id))
+    }
 }
 
 /// A general trait for location messages.
@@ -29,10 +59,69 @@ pub trait Location: MediaMessage {
 pub trait Photo: MediaMessage {
     /// The photo of the message.
     fn photo(&self) -> &[PhotoSize];
+
+    /// Downloads one of the photo's sizes, e.g. the largest one which is
+    /// the last in [`photo`]. See [`Bot::download_by_id`] for details.
+    ///
+    /// [`photo`]: Self::photo
+    /// [`Bot::download_by_id`]: crate::Bot::download_by_id
+    fn download_photo(&self, size: &PhotoSize) -> Download<'_> {
+        Box::pin(self.bot().download_by_id(size.file_id.clone()))
+    }
 }
 
 /// A general trait for video messages.
 pub trait Video: MediaMessage {
     /// The video of the message.
     fn video(&self) -> &types::Video;
+
+    /// Downloads the video. See [`Bot::download_by_id`] for details.
+    ///
+    /// [`Bot::download_by_id`]: crate::Bot::download_by_id
+    fn download_video(&self) -> Download<'_> {
+        let file_id = self.video().file_id.clone();
+        Box::pin(self.bot().download_by_id(file_id))
+    }
+}
+
+/// A general trait for sticker messages.
+pub trait Sticker: MediaMessage {
+    /// The sticker of the message.
+    fn sticker(&self) -> &types::Sticker;
+
+    /// Downloads the sticker. See [`Bot::download_by_id`] for details.
+    ///
+    /// [`Bot::download_by_id`]: crate::Bot::download_by_id
+    fn download_sticker(&self) -> Download<'_> {
+        let file_id = self.sticker().file_id.clone();
+        Box::pin(self.bot().download_by_id(file_id))
+    }
+}
+
+/// A general trait for video note messages.
+pub trait VideoNote: MediaMessage {
+    /// The video note of the message.
+    fn video_note(&self) -> &types::VideoNote;
+
+    /// Downloads the video note. See [`Bot::download_by_id`] for details.
+    ///
+    /// [`Bot::download_by_id`]: crate::Bot::download_by_id
+    fn download_video_note(&self) -> Download<'_> {
+        let file_id = self.video_note().file_id.clone();
+        Box::pin(self.bot().download_by_id(file_id))
+    }
+}
+
+/// A general trait for voice messages.
+pub trait Voice: MediaMessage {
+    /// The voice of the message.
+    fn voice(&self) -> &types::Voice;
+
+    /// Downloads the voice. See [`Bot::download_by_id`] for details.
+    ///
+    /// [`Bot::download_by_id`]: crate::Bot::download_by_id
+    fn download_voice(&self) -> Download<'_> {
+        let file_id = self.voice().file_id.clone();
+        Box::pin(self.bot().download_by_id(file_id))
+    }
 }
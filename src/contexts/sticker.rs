@@ -1,4 +1,4 @@
-use crate::types;
+use crate::{contexts::fields, types};
 
 media_message! {
     struct Sticker {
@@ -10,3 +10,10 @@ media_message! {
         Self { }
     }
 }
+
+impl fields::Sticker for Sticker {
+    #[must_use]
+    fn sticker(&self) -> &types::Sticker {
+        &self.sticker
+    }
+}
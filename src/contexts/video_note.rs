@@ -1,4 +1,4 @@
-use crate::types;
+use crate::{contexts::fields, types};
 
 media_message! {
     struct VideoNote {
@@ -10,3 +10,10 @@ media_message! {
         Self { }
     }
 }
+
+impl fields::VideoNote for VideoNote {
+    #[must_use]
+    fn video_note(&self) -> &types::VideoNote {
+        &self.video_note
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    contexts::fields::{AnyText, Caption},
+    contexts::fields::{self, AnyText, Caption},
     types::{self, message::Text},
 };
 
@@ -31,3 +31,10 @@ impl AnyText for Voice {
         &self.caption
     }
 }
+
+impl fields::Voice for Voice {
+    #[must_use]
+    fn voice(&self) -> &types::Voice {
+        &self.voice
+    }
+}
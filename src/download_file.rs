@@ -1,7 +1,49 @@
-use crate::{bot::InnerBot, errors, types::File};
-use hyper::{body::HttpBody, http::uri::PathAndQuery, StatusCode, Uri};
-use std::{error::Error, fmt::Write, path::Path};
-use tokio::fs;
+use crate::{
+    bot::{InnerBot, RetryPolicy},
+    errors,
+    methods::GetFile,
+    types::file::{self, File},
+};
+use futures::{
+    pin_mut,
+    stream::{self, BoxStream, Stream, StreamExt},
+};
+use headers::{ContentRange, HeaderMapExt, Range};
+use hyper::{
+    body::{Bytes, HttpBody},
+    http::uri::PathAndQuery,
+    Body, HeaderMap, Request, StatusCode, Uri,
+};
+use std::{
+    error::Error,
+    fmt::Write,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt},
+    time::{sleep, timeout},
+};
+
+/// How many bytes are read from a local file at once.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// For how long a resumable download waits for the server to send data if
+/// the bot has no request timeout configured.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+type Chunks = BoxStream<'static, Result<Bytes, errors::Download>>;
+
+/// How much of a file has been downloaded so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DownloadProgress {
+    /// How many bytes have been downloaded.
+    pub downloaded: u64,
+    /// The size of the file, if known.
+    pub total: Option<u64>,
+}
 
 fn construct_uri(
     bot: &InnerBot,
@@ -46,7 +88,11 @@ pub async fn download_file(
     let url = construct_uri(bot, path)
         .expect("[tbot] Download URI construction failed");
 
-    let (parts, mut body) = bot.client().get(url).await?.into_parts();
+    let mut request = Request::new(Body::empty());
+    *request.uri_mut() = url;
+
+    let (parts, mut body) =
+        bot.transport().request(request).await?.into_parts();
 
     if parts.status != StatusCode::OK {
         return Err(errors::Download::InvalidStatusCode(parts.status));
@@ -64,3 +110,611 @@ pub async fn download_file(
 
     Ok(response)
 }
+
+/// Calls `getFile` and remembers the file's path.
+async fn get_file(
+    bot: &InnerBot,
+    id: &file::Id,
+) -> Result<File, errors::Download> {
+    let file = GetFile::new(bot, id.clone()).call().await?;
+    bot.file_paths().insert(id.clone(), file.clone());
+    Ok(file)
+}
+
+pub async fn download_by_id(
+    bot: &InnerBot,
+    id: &file::Id,
+) -> Result<Vec<u8>, errors::Download> {
+    let file = if let Some(file) = bot.file_paths().get(id) {
+        file
+    } else {
+        let file = get_file(bot, id).await?;
+        return download_file(bot, &file).await;
+    };
+
+    match download_file(bot, &file).await {
+        // the path expired earlier than expected
+        Err(errors::Download::InvalidStatusCode(StatusCode::NOT_FOUND)) => {
+            bot.file_paths().remove(id);
+            let file = get_file(bot, id).await?;
+            download_file(bot, &file).await
+        }
+        result => result,
+    }
+}
+
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get("Content-Length")
+        .and_then(|x| x.to_str().ok().and_then(|x| x.parse().ok()))
+}
+
+/// Starts downloading the file and returns its chunks along with the file's
+/// size, if the server reported it.
+async fn chunks(
+    bot: &InnerBot,
+    file: &File,
+) -> Result<(Chunks, Option<u64>), errors::Download> {
+    let path = match &file.path {
+        Some(path) => path,
+        None => return Err(errors::Download::NoPath),
+    };
+
+    if Path::new(&path).is_absolute() {
+        let file = fs::File::open(&path).await?;
+        let length = file.metadata().await?.len();
+
+        let chunks = stream::try_unfold(file, |mut file| async move {
+            let mut buffer = vec![0; CHUNK_SIZE];
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                return Ok(None);
+            }
+
+            buffer.truncate(read);
+            Ok(Some((Bytes::from(buffer), file)))
+        });
+
+        return Ok((Box::pin(chunks), Some(length)));
+    }
+
+    let url = construct_uri(bot, path)
+        .expect("[tbot] Download URI construction failed");
+
+    let mut request = Request::new(Body::empty());
+    *request.uri_mut() = url;
+
+    let (parts, body) = bot.transport().request(request).await?.into_parts();
+
+    if parts.status != StatusCode::OK {
+        return Err(errors::Download::InvalidStatusCode(parts.status));
+    }
+
+    let chunks = stream::unfold(body, |mut body| async move {
+        let chunk = body.data().await?;
+        Some((chunk.map_err(Into::into), body))
+    });
+
+    Ok((Box::pin(chunks), content_length(&parts.headers)))
+}
+
+/// Reports progress for each chunk and checks that the downloaded size
+/// matches the expected one.
+fn track<F>(
+    chunks: Chunks,
+    expected: Option<u64>,
+    on_progress: F,
+) -> impl Stream<Item = Result<Bytes, errors::Download>>
+where
+    F: FnMut(DownloadProgress),
+{
+    let mismatch = move |received| errors::Download::SizeMismatch {
+        expected: expected.unwrap_or_default(),
+        received,
+    };
+
+    stream::unfold(Some((chunks, 0, on_progress)), move |state| async move {
+        let (mut chunks, mut downloaded, mut on_progress) = state?;
+
+        let chunk = match chunks.next().await {
+            Some(Ok(chunk)) => chunk,
+            Some(Err(error)) => return Some((Err(error), None)),
+            None if expected.map_or(true, |x| x == downloaded) => return None,
+            None => return Some((Err(mismatch(downloaded)), None)),
+        };
+
+        downloaded += chunk.len() as u64;
+        if expected.map_or(false, |x| downloaded > x) {
+            return Some((Err(mismatch(downloaded)), None));
+        }
+
+        on_progress(DownloadProgress {
+            downloaded,
+            total: expected,
+        });
+
+        Some((Ok(chunk), Some((chunks, downloaded, on_progress))))
+    })
+}
+
+pub async fn download_file_stream<F>(
+    bot: &InnerBot,
+    file: &File,
+    on_progress: F,
+) -> Result<Chunks, errors::Download>
+where
+    F: FnMut(DownloadProgress) + Send + 'static,
+{
+    let (chunks, length) = chunks(bot, file).await?;
+    let expected = file.size.map(u64::from).or(length);
+
+    Ok(Box::pin(track(chunks, expected, on_progress)))
+}
+
+pub async fn download_file_to<W, F>(
+    bot: &InnerBot,
+    file: &File,
+    mut writer: W,
+    on_progress: F,
+) -> Result<(), errors::Download>
+where
+    W: AsyncWrite + Unpin,
+    F: FnMut(DownloadProgress),
+{
+    let (chunks, length) = chunks(bot, file).await?;
+    let expected = file.size.map(u64::from).or(length);
+
+    let chunks = track(chunks, expected, on_progress);
+    pin_mut!(chunks);
+
+    while let Some(chunk) = chunks.next().await {
+        writer.write_all(&chunk?).await?;
+    }
+
+    writer.flush().await?;
+    Ok(())
+}
+
+pub async fn download_file_to_path<F>(
+    bot: &InnerBot,
+    file: &File,
+    path: &Path,
+    on_progress: F,
+) -> Result<(), errors::Download>
+where
+    F: FnMut(DownloadProgress),
+{
+    let temp_path = sidecar_path(path, file, "tmp");
+    let mut writer = fs::File::create(&temp_path).await?;
+    let result = download_file_to(bot, file, &mut writer, on_progress).await;
+    drop(writer);
+
+    match result {
+        Ok(()) => fs::rename(&temp_path, path).await.map_err(Into::into),
+        Err(error) => {
+            // the partial file is useless, and the original error is more
+            // important than a failure to remove it
+            let _ = fs::remove_file(&temp_path).await;
+            Err(error)
+        }
+    }
+}
+
+/// Requests the file's bytes starting at `offset`. Returns the chunks along
+/// with the offset they actually start at, which is `0` if the server ignored
+/// the range, and the file's size, if the server reported it.
+async fn request_range(
+    bot: &InnerBot,
+    path: &str,
+    offset: u64,
+) -> Result<(Chunks, u64, Option<u64>), errors::Download> {
+    let url = construct_uri(bot, path)
+        .expect("[tbot] Download URI construction failed");
+
+    let mut request = Request::new(Body::empty());
+    *request.uri_mut() = url;
+    if offset > 0 {
+        let range = Range::bytes(offset..).unwrap();
+        request.headers_mut().typed_insert(range);
+    }
+
+    let (parts, body) = bot.transport().request(request).await?.into_parts();
+
+    let (start, size) = match parts.status {
+        StatusCode::OK => (0, content_length(&parts.headers)),
+        StatusCode::PARTIAL_CONTENT => {
+            let range = parts.headers.typed_get::<ContentRange>();
+            match range.as_ref().and_then(ContentRange::bytes_range) {
+                Some((start, _)) if start == offset => {
+                    (start, range.and_then(|range| range.bytes_len()))
+                }
+                _ => {
+                    return Err(errors::Download::InvalidStatusCode(
+                        parts.status,
+                    ))
+                }
+            }
+        }
+        status => return Err(errors::Download::InvalidStatusCode(status)),
+    };
+
+    let chunks = stream::unfold(body, |mut body| async move {
+        let chunk = body.data().await?;
+        Some((chunk.map_err(Into::into), body))
+    });
+
+    Ok((Box::pin(chunks), start, size))
+}
+
+/// The state of a resumable download between attempts.
+struct Resumable<'a, F> {
+    writer: fs::File,
+    downloaded: u64,
+    expected: Option<u64>,
+    idle_timeout: Duration,
+    on_progress: &'a mut F,
+}
+
+impl<F> Resumable<'_, F>
+where
+    F: FnMut(DownloadProgress),
+{
+    async fn truncate(&mut self) -> Result<(), errors::Download> {
+        self.writer.set_len(0).await?;
+        self.writer.seek(SeekFrom::Start(0)).await?;
+        self.downloaded = 0;
+        Ok(())
+    }
+
+    fn check_size(&self) -> Result<(), errors::Download> {
+        match self.expected {
+            Some(expected) if expected != self.downloaded => {
+                Err(errors::Download::SizeMismatch {
+                    expected,
+                    received: self.downloaded,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn resume(
+        &mut self,
+        bot: &InnerBot,
+        path: &str,
+    ) -> Result<(), errors::Download> {
+        let request = request_range(bot, path, self.downloaded);
+        let (mut chunks, start, size) = timeout(self.idle_timeout, request)
+            .await
+            .map_err(|_| errors::Download::Timeout)??;
+
+        if start != self.downloaded {
+            self.truncate().await?;
+        }
+
+        match (self.expected, size) {
+            (Some(expected), Some(size)) if expected != size => {
+                return Err(errors::Download::SizeMismatch {
+                    expected,
+                    received: size,
+                });
+            }
+            (None, size) => self.expected = size,
+            _ => (),
+        }
+
+        loop {
+            let chunk = timeout(self.idle_timeout, chunks.next())
+                .await
+                .map_err(|_| errors::Download::Timeout)?;
+            let chunk = match chunk {
+                Some(chunk) => chunk?,
+                None => break,
+            };
+
+            self.writer.write_all(&chunk).await?;
+            self.downloaded += chunk.len() as u64;
+
+            if self.expected.map_or(false, |x| self.downloaded > x) {
+                return self.check_size();
+            }
+
+            (self.on_progress)(DownloadProgress {
+                downloaded: self.downloaded,
+                total: self.expected,
+            });
+        }
+
+        self.writer.flush().await?;
+        self.check_size()
+    }
+
+    /// Downloads the rest of the file, resuming it according to
+    /// `retry_policy` if it fails.
+    async fn download(
+        &mut self,
+        bot: &InnerBot,
+        path: &str,
+        retry_policy: RetryPolicy,
+    ) -> Result<(), errors::Download> {
+        match self.expected {
+            Some(expected) if self.downloaded == expected => {
+                (self.on_progress)(DownloadProgress {
+                    downloaded: expected,
+                    total: Some(expected),
+                });
+                return Ok(());
+            }
+            Some(expected) if self.downloaded > expected => {
+                self.truncate().await?;
+            }
+            _ => (),
+        }
+
+        let mut attempt = 1;
+        loop {
+            let error = match self.resume(bot, path).await {
+                Ok(()) => return Ok(()),
+                Err(error) => error,
+            };
+
+            // the partial file doesn't match the file on the server
+            let is_unsatisfiable = matches!(
+                error,
+                errors::Download::InvalidStatusCode(
+                    StatusCode::RANGE_NOT_SATISFIABLE
+                )
+            );
+            if is_unsatisfiable && self.downloaded > 0 {
+                self.truncate().await?;
+                continue;
+            }
+
+            match retry_policy.download_retry_delay(attempt, &error) {
+                Some(delay) => sleep(delay).await,
+                None => return Err(error),
+            }
+
+            attempt += 1;
+        }
+    }
+}
+
+/// The path where a file is downloaded to before it's complete. It's tied
+/// to the file's unique ID, so that a partial download of another file is
+/// never resumed.
+fn sidecar_path(path: &Path, file: &File, extension: &str) -> PathBuf {
+    let mut sidecar_path = path.as_os_str().to_owned();
+    sidecar_path.push(format!(".{}.{}", file.unique_id, extension));
+    PathBuf::from(sidecar_path)
+}
+
+pub async fn download_file_resumable<F>(
+    bot: &InnerBot,
+    file: &File,
+    path: &Path,
+    retry_policy: RetryPolicy,
+    mut on_progress: F,
+) -> Result<(), errors::Download>
+where
+    F: FnMut(DownloadProgress),
+{
+    let file_path = match &file.path {
+        Some(file_path) => file_path,
+        None => return Err(errors::Download::NoPath),
+    };
+
+    if Path::new(file_path).is_absolute() {
+        return download_file_to_path(bot, file, path, on_progress).await;
+    }
+
+    let part_path = sidecar_path(path, file, "part");
+    let mut writer = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(&part_path)
+        .await?;
+    let downloaded = writer.seek(SeekFrom::End(0)).await?;
+
+    let mut download = Resumable {
+        writer,
+        downloaded,
+        expected: file.size.map(u64::from),
+        idle_timeout: bot.request_timeout().unwrap_or(IDLE_TIMEOUT),
+        on_progress: &mut on_progress,
+    };
+    download.download(bot, file_path, retry_policy).await?;
+
+    drop(download);
+    fs::rename(&part_path, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        connectors::{Transport, TransportError},
+        token::Token,
+        types::file,
+    };
+    use futures::future::BoxFuture;
+    use hyper::Response;
+    use std::{
+        convert::TryFrom,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    const CONTENTS: &[u8] = b"0123456789";
+
+    fn partial_content(offset: u64, body: Body) -> Response<Body> {
+        let mut response = Response::new(body);
+        *response.status_mut() = StatusCode::PARTIAL_CONTENT;
+        let range =
+            ContentRange::bytes(offset.., CONTENTS.len() as u64).unwrap();
+        response.headers_mut().typed_insert(range);
+        response
+    }
+
+    /// Serves `CONTENTS`, but the first response fails after 4 bytes and
+    /// the second one stalls after 3 more bytes.
+    #[derive(Debug, Default)]
+    struct Flaky(Arc<Mutex<Vec<Option<u64>>>>);
+
+    impl Transport for Flaky {
+        fn request(
+            &self,
+            request: Request<Body>,
+        ) -> BoxFuture<'static, Result<Response<Body>, TransportError>>
+        {
+            let offset = request
+                .headers()
+                .get(hyper::header::RANGE)
+                .and_then(|range| range.to_str().ok())
+                .and_then(|range| range.strip_prefix("bytes="))
+                .and_then(|range| range.trim_end_matches('-').parse().ok());
+
+            let response = match offset {
+                None => Response::new(Body::wrap_stream(stream::iter(vec![
+                    Ok(Bytes::from_static(&CONTENTS[..4])),
+                    Err(std::io::Error::from(std::io::ErrorKind::BrokenPipe)),
+                ]))),
+                Some(4) => {
+                    let chunk = Bytes::from_static(&CONTENTS[4..7]);
+                    let body =
+                        stream::once(
+                            async move { Ok::<_, std::io::Error>(chunk) },
+                        )
+                        .chain(stream::pending());
+                    partial_content(4, Body::wrap_stream(body))
+                }
+                Some(offset) => {
+                    let start = usize::try_from(offset).unwrap();
+                    partial_content(offset, Body::from(&CONTENTS[start..]))
+                }
+            };
+            self.0.lock().unwrap().push(offset);
+
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    #[tokio::test]
+    async fn resumes_interrupted_downloads() {
+        let requests = Arc::default();
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Flaky(Arc::clone(&requests)));
+        bot.set_request_timeout(Duration::from_millis(100));
+
+        let file = File {
+            id: file::Id::from("id"),
+            unique_id: String::from("unique_id"),
+            size: Some(10),
+            path: Some(String::from("documents/file.txt")),
+        };
+        let path = std::env::temp_dir()
+            .join(format!("tbot-resumable-{}.txt", std::process::id()));
+        let part_path = sidecar_path(&path, &file, "part");
+
+        // a stale part of another file must not be resumed
+        fs::write(&path, b"stale").await.unwrap();
+        let policy = RetryPolicy::new(3).backoff(Duration::from_millis(0));
+
+        download_file_resumable(&bot, &file, &path, policy, |_| ())
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read(&path).await.unwrap(), CONTENTS);
+        assert!(fs::metadata(&part_path).await.is_err());
+        assert_eq!(*requests.lock().unwrap(), [None, Some(4), Some(7)]);
+        fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn keeps_existing_files_if_downloads_fail() {
+        let file = File {
+            id: file::Id::from("id"),
+            unique_id: String::from("unique_id"),
+            size: Some(10),
+            path: Some(String::from("documents/1.txt")),
+        };
+        let path = std::env::temp_dir()
+            .join(format!("tbot-to-path-{}.txt", std::process::id()));
+        let temp_path = sidecar_path(&path, &file, "tmp");
+        fs::write(&path, b"old").await.unwrap();
+
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Flaky::default());
+        let result = download_file_to_path(&bot, &file, &path, |_| ()).await;
+        assert!(result.unwrap_err().is_network());
+        assert_eq!(fs::read(&path).await.unwrap(), b"old");
+        assert!(fs::metadata(&temp_path).await.is_err());
+
+        bot.set_transport(ExpiringPaths::default());
+        download_file_to_path(&bot, &file, &path, |_| ())
+            .await
+            .unwrap();
+        assert_eq!(fs::read(&path).await.unwrap(), CONTENTS);
+        assert!(fs::metadata(&temp_path).await.is_err());
+        fs::remove_file(&path).await.unwrap();
+    }
+
+    /// Serves `getFile` with a new path each time, and serves each path once.
+    #[derive(Debug, Default)]
+    struct ExpiringPaths(Arc<Mutex<Vec<String>>>);
+
+    impl Transport for ExpiringPaths {
+        fn request(
+            &self,
+            request: Request<Body>,
+        ) -> BoxFuture<'static, Result<Response<Body>, TransportError>>
+        {
+            let path = request.uri().path().to_owned();
+            let mut requests = self.0.lock().unwrap();
+            let paths_fetched =
+                requests.iter().filter(|x| x.ends_with("getFile")).count();
+
+            let response = if path.ends_with("getFile") {
+                Response::new(Body::from(format!(
+                    r#"{{"ok":true,"result":{{"file_id":"id",
+                    "file_unique_id":"unique_id","file_size":10,
+                    "file_path":"documents/{}.txt"}}}}"#,
+                    paths_fetched + 1,
+                )))
+            } else if requests.contains(&path) {
+                let mut response = Response::new(Body::empty());
+                *response.status_mut() = StatusCode::NOT_FOUND;
+                response
+            } else {
+                Response::new(Body::from(CONTENTS))
+            };
+            requests.push(path);
+
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    #[tokio::test]
+    async fn refreshes_expired_paths() {
+        let requests = Arc::default();
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(ExpiringPaths(Arc::clone(&requests)));
+
+        let id = file::Id::from("id");
+        assert_eq!(download_by_id(&bot, &id).await.unwrap(), CONTENTS);
+        assert_eq!(download_by_id(&bot, &id).await.unwrap(), CONTENTS);
+
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [
+                "/botTOKEN/getFile",
+                "/file/botTOKEN/documents/1.txt",
+                "/file/botTOKEN/documents/1.txt",
+                "/botTOKEN/getFile",
+                "/file/botTOKEN/documents/2.txt",
+            ]
+        );
+    }
+}
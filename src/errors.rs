@@ -6,8 +6,11 @@ mod https_webhook;
 mod method_call;
 mod polling;
 mod polling_setup;
+mod request_error_kind;
+mod webhook_setup;
 
 pub use {
     download::Download, http_webhook::HttpWebhook, https_webhook::HttpsWebhook,
     method_call::MethodCall, polling::Polling, polling_setup::PollingSetup,
+    request_error_kind::RequestErrorKind, webhook_setup::WebhookSetup,
 };
@@ -1,3 +1,5 @@
+use super::MethodCall;
+use crate::connectors::TransportError;
 use hyper::StatusCode;
 use is_macro::Is;
 use std::{
@@ -11,13 +13,30 @@ use std::{
 pub enum Download {
     /// The provided file's `path` is `None`.
     NoPath,
+    /// Failed to get the file's path when downloading a file by its ID.
+    GetFile(MethodCall),
     /// A network error.
     Network(hyper::Error),
+    /// An error of a custom [`Transport`].
+    ///
+    /// [`Transport`]: crate::connectors::Transport
+    Transport(TransportError),
     /// Telegram returned a status code different from `200`.
     InvalidStatusCode(StatusCode),
-    /// Failed to read a local file. This can only be returned if you use
-    /// a self-hosted Bot API server.
+    /// The server didn't send any data for too long while resuming
+    /// a download.
+    Timeout,
+    /// Failed to read a local file, which can only happen if you use
+    /// a self-hosted Bot API server, or to write the downloaded file.
     Io(io::Error),
+    /// The downloaded file's size differs from the expected one, e.g. because
+    /// the transfer was interrupted.
+    SizeMismatch {
+        /// The size reported by the Bot API.
+        expected: u64,
+        /// How many bytes were received.
+        received: u64,
+    },
 }
 
 impl Display for Download {
@@ -27,28 +46,69 @@ impl Display for Download {
                 formatter,
                 "A file could not be downloaded because of a missing `path`.",
             ),
+            Self::GetFile(error) => write!(
+                formatter,
+                "A file could not be downloaded because its path could not be \
+                 fetched: {}",
+                error,
+            ),
             Self::Network(error) => write!(
                 formatter,
                 "A file could not be downloaded because of a network error: {}",
                 error,
             ),
+            Self::Transport(error) => write!(
+                formatter,
+                "A file could not be downloaded because of a transport error: {}",
+                error,
+            ),
             Self::InvalidStatusCode(code) => write!(
                 formatter,
                 "A file could not be downloaded because Telegram responded \
                  with {} instead of 200 OK.",
                 code,
             ),
+            Self::Timeout => write!(
+                formatter,
+                "A file could not be downloaded because the server didn't \
+                 send any data for too long.",
+            ),
             Self::Io(error) => write!(
                 formatter,
-                "The server returned a local path to the file, but an error \
-                 occured reading it: {}",
+                "A file could not be downloaded because of an I/O error: {}",
                 error
             ),
+            Self::SizeMismatch { expected, received } => write!(
+                formatter,
+                "A file could not be downloaded because {} bytes were received \
+                 while {} bytes were expected.",
+                received, expected,
+            ),
         }
     }
 }
 
-impl Error for Download {}
+impl Error for Download {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::GetFile(error) => Some(error),
+            Self::Network(error) => Some(error),
+            Self::Transport(error) => Some(&**error),
+            Self::Io(error) => Some(error),
+            Self::NoPath
+            | Self::InvalidStatusCode(..)
+            | Self::Timeout
+            | Self::SizeMismatch { .. } => None,
+        }
+    }
+}
+
+impl From<MethodCall> for Download {
+    #[must_use]
+    fn from(error: MethodCall) -> Self {
+        Self::GetFile(error)
+    }
+}
 
 impl From<hyper::Error> for Download {
     #[must_use]
@@ -57,6 +117,16 @@ impl From<hyper::Error> for Download {
     }
 }
 
+impl From<TransportError> for Download {
+    #[must_use]
+    fn from(error: TransportError) -> Self {
+        match error.downcast() {
+            Ok(error) => Self::Network(*error),
+            Err(error) => Self::Transport(error),
+        }
+    }
+}
+
 impl From<StatusCode> for Download {
     #[must_use]
     fn from(error: StatusCode) -> Self {
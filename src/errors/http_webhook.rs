@@ -1,4 +1,4 @@
-use crate::errors::MethodCall;
+use crate::errors::{MethodCall, WebhookSetup};
 use is_macro::Is;
 use std::{
     error::Error,
@@ -17,6 +17,8 @@ pub enum HttpWebhook {
     SetMyCommands(MethodCall),
     /// Calling the `setMyCommands` method timed out.
     SetMyCommandsTimeout(Elapsed),
+    /// An error while binding to a Unix domain socket.
+    Bind(std::io::Error),
     /// An error while running the server.
     Server(hyper::Error),
 }
@@ -48,6 +50,12 @@ impl Display for HttpWebhook {
                 timed out: {}",
                 timeout,
             ),
+            Self::Bind(error) => write!(
+                formatter,
+                "The webhook event loop failed because binding to a Unix \
+                 domain socket failed with an error: {}",
+                error,
+            ),
             Self::Server(error) => write!(
                 formatter,
                 "The webhook event loop failed because the server returned \
@@ -60,6 +68,22 @@ impl Display for HttpWebhook {
 
 impl Error for HttpWebhook {}
 
+impl From<WebhookSetup> for HttpWebhook {
+    #[must_use]
+    fn from(error: WebhookSetup) -> Self {
+        match error {
+            WebhookSetup::SetWebhook(error) => Self::SetWebhook(error),
+            WebhookSetup::SetWebhookTimeout(timeout) => {
+                Self::SetWebhookTimeout(timeout)
+            }
+            WebhookSetup::SetMyCommands(error) => Self::SetMyCommands(error),
+            WebhookSetup::SetMyCommandsTimeout(timeout) => {
+                Self::SetMyCommandsTimeout(timeout)
+            }
+        }
+    }
+}
+
 impl From<MethodCall> for HttpWebhook {
     #[must_use]
     fn from(error: MethodCall) -> Self {
@@ -80,3 +104,10 @@ impl From<hyper::Error> for HttpWebhook {
         Self::Server(error)
     }
 }
+
+impl From<std::io::Error> for HttpWebhook {
+    #[must_use]
+    fn from(error: std::io::Error) -> Self {
+        Self::Bind(error)
+    }
+}
@@ -1,4 +1,4 @@
-use super::MethodCall;
+use super::{MethodCall, WebhookSetup};
 use is_macro::Is;
 use std::{
     error::Error,
@@ -22,6 +22,8 @@ pub enum HttpsWebhook {
         #[cfg(feature = "tls")] native_tls::Error,
         #[cfg(feature = "rustls")] tokio_rustls::rustls::TLSError,
     ),
+    /// An error while reading the certificate or the private key from disk.
+    Certificate(std::io::Error),
     /// An error while binding to a port.
     Bind(std::io::Error),
     /// An error while running the server.
@@ -61,6 +63,12 @@ impl Display for HttpsWebhook {
                  failed with an error: {}",
                 timeout,
             ),
+            Self::Certificate(error) => write!(
+                formatter,
+                "The webhook event loop failed because reading the certificate \
+                 or the private key failed with an error: {}",
+                error,
+            ),
             Self::Bind(timeout) => write!(
                 formatter,
                 "The webhook event loop failed because failed to bind to a \
@@ -79,6 +87,22 @@ impl Display for HttpsWebhook {
 
 impl Error for HttpsWebhook {}
 
+impl From<WebhookSetup> for HttpsWebhook {
+    #[must_use]
+    fn from(error: WebhookSetup) -> Self {
+        match error {
+            WebhookSetup::SetWebhook(error) => Self::SetWebhook(error),
+            WebhookSetup::SetWebhookTimeout(timeout) => {
+                Self::SetWebhookTimeout(timeout)
+            }
+            WebhookSetup::SetMyCommands(error) => Self::SetMyCommands(error),
+            WebhookSetup::SetMyCommandsTimeout(timeout) => {
+                Self::SetMyCommandsTimeout(timeout)
+            }
+        }
+    }
+}
+
 impl From<MethodCall> for HttpsWebhook {
     #[must_use]
     fn from(error: MethodCall) -> Self {
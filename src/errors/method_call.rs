@@ -1,8 +1,11 @@
+use super::RequestErrorKind;
+use crate::connectors::TransportError;
 use crate::types::chat;
 use is_macro::Is;
 use std::{
     error::Error,
     fmt::{self, Display, Formatter},
+    io,
 };
 
 /// Represents possible errors that may happen during a method call.
@@ -10,8 +13,24 @@ use std::{
 pub enum MethodCall {
     /// A network error.
     Network(hyper::Error),
+    /// An error of a custom [`Transport`].
+    ///
+    /// [`Transport`]: crate::connectors::Transport
+    Transport(TransportError),
     /// Bot API is likely to be down.
     OutOfService,
+    /// The Bot API didn't respond in time.
+    Timeout,
+    /// Failed to read a file to upload, or the file was streamed from
+    /// a reader which had already been read by a previous attempt.
+    Upload(io::Error),
+    /// An interceptor vetoed the call with the following reason, so it wasn't
+    /// sent to the Bot API.
+    Vetoed(String),
+    /// The call refers to a file on the Bot API server's machine, but the file
+    /// doesn't exist or the bot isn't configured to use a self-hosted server,
+    /// so the call wasn't sent.
+    LocalFile,
     /// Failed to parse the response.
     Parse {
         /// The response which failed to parse.
@@ -33,6 +52,24 @@ pub enum MethodCall {
     },
 }
 
+impl MethodCall {
+    /// Classifies the error the Bot API responded with, e.g. to tell if the user
+    /// blocked the bot. Returns `None` if this isn't a [`RequestError`].
+    ///
+    /// [`RequestError`]: Self::RequestError
+    #[must_use]
+    pub fn request_error_kind(&self) -> Option<RequestErrorKind> {
+        match self {
+            Self::RequestError {
+                description,
+                error_code,
+                ..
+            } => Some(RequestErrorKind::classify(*error_code, description)),
+            _ => None,
+        }
+    }
+}
+
 impl Display for MethodCall {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         match self {
@@ -41,10 +78,37 @@ impl Display for MethodCall {
                 "A method call failed because of a network error: {}",
                 error,
             ),
+            Self::Transport(error) => write!(
+                formatter,
+                "A method call failed because of a transport error: {}",
+                error,
+            ),
             Self::OutOfService => write!(
                 formatter,
                 "A method call failed because Telegram is out of service.",
             ),
+            Self::Timeout => write!(
+                formatter,
+                "A method call failed because the Bot API didn't respond \
+                 in time.",
+            ),
+            Self::Upload(error) => write!(
+                formatter,
+                "A method call failed because a file to upload could not be \
+                 read: {}",
+                error,
+            ),
+            Self::Vetoed(reason) => write!(
+                formatter,
+                "A method call was vetoed by an interceptor: {}",
+                reason,
+            ),
+            Self::LocalFile => write!(
+                formatter,
+                "A method call refers to a local file which doesn't exist or \
+                 which only a self-hosted Bot API server can read. Configure \
+                 its URI using `bot::Builder::server_uri`.",
+            ),
             Self::Parse { response, error } => write!(
                 formatter,
                 "A method call failed because `tbot` failed to parse the \
@@ -84,3 +148,13 @@ impl From<hyper::Error> for MethodCall {
         Self::Network(error)
     }
 }
+
+impl From<TransportError> for MethodCall {
+    #[must_use]
+    fn from(error: TransportError) -> Self {
+        match error.downcast() {
+            Ok(error) => Self::Network(*error),
+            Err(error) => Self::Transport(error),
+        }
+    }
+}
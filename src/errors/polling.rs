@@ -8,6 +8,12 @@ pub enum Polling {
     /// Calling `GetUpdates` resulted in an error.
     Fetching(MethodCall),
     /// Calling `GetUpdates` timed out.
+    ///
+    /// `tbot` no longer constructs this variant and reports timeouts as
+    /// [`Fetching`] wrapping [`MethodCall::Timeout`] instead. It's only kept
+    /// for compatibility.
+    ///
+    /// [`Fetching`]: Self::Fetching
     Timeout(Elapsed),
 }
 
@@ -0,0 +1,144 @@
+use is_macro::Is;
+
+/// Well-known errors the Bot API responds with, classified by their
+/// description.
+///
+/// The Bot API doesn't provide machine-readable error codes beyond HTTP-like
+/// `error_code`s, so `tbot` recognizes these errors by their descriptions.
+/// Errors `tbot` doesn't recognize are classified as [`Other`]; in this case,
+/// inspect the raw description in [`MethodCall::RequestError`].
+///
+/// [`Other`]: Self::Other
+/// [`MethodCall::RequestError`]: super::MethodCall::RequestError
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Is)]
+#[non_exhaustive]
+pub enum RequestErrorKind {
+    /// The user blocked the bot.
+    BotBlocked,
+    /// The user deleted their account.
+    UserDeactivated,
+    /// The bot was kicked from the group or the channel.
+    BotKicked,
+    /// The chat doesn't exist or the bot has no access to it.
+    ChatNotFound,
+    /// The new content of the message is the same as the current one.
+    MessageNotModified,
+    /// The message to edit doesn't exist.
+    MessageToEditNotFound,
+    /// The message to delete doesn't exist or can't be deleted.
+    MessageToDeleteNotFound,
+    /// The bot lacks administrator rights for the action.
+    NotEnoughRights,
+    /// The callback or inline query is too old to be answered, or its ID is
+    /// invalid.
+    QueryTooOld,
+    /// The bot exceeded flood threshold.
+    TooManyRequests,
+    /// The file ID is invalid or was issued to another bot.
+    WrongFileId,
+    /// An error `tbot` doesn't recognize.
+    Other,
+}
+
+impl RequestErrorKind {
+    pub(crate) fn classify(error_code: u16, description: &str) -> Self {
+        let description = description.to_lowercase();
+        let contains = |needle| description.contains(needle);
+
+        if error_code == 429 {
+            Self::TooManyRequests
+        } else if contains("bot was blocked by the user") {
+            Self::BotBlocked
+        } else if contains("user is deactivated") {
+            Self::UserDeactivated
+        } else if contains("bot was kicked") {
+            Self::BotKicked
+        } else if contains("chat not found") {
+            Self::ChatNotFound
+        } else if contains("message is not modified") {
+            Self::MessageNotModified
+        } else if contains("message to edit not found") {
+            Self::MessageToEditNotFound
+        } else if contains("message to delete not found")
+            || contains("message can't be deleted")
+        {
+            Self::MessageToDeleteNotFound
+        } else if contains("not enough rights")
+            || contains("have no rights")
+            || contains("need administrator rights")
+        {
+            Self::NotEnoughRights
+        } else if contains("query is too old") {
+            Self::QueryTooOld
+        } else if contains("wrong file identifier")
+            || contains("wrong remote file identifier")
+            || contains("wrong file_id")
+        {
+            Self::WrongFileId
+        } else {
+            Self::Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RequestErrorKind;
+
+    #[test]
+    fn classifies_descriptions() {
+        let cases = [
+            (
+                403,
+                "Forbidden: bot was blocked by the user",
+                RequestErrorKind::BotBlocked,
+            ),
+            (
+                403,
+                "Forbidden: user is deactivated",
+                RequestErrorKind::UserDeactivated,
+            ),
+            (
+                400,
+                "Bad Request: chat not found",
+                RequestErrorKind::ChatNotFound,
+            ),
+            (
+                400,
+                "Bad Request: message is not modified: specified new message \
+                 content and reply markup are exactly the same as a current \
+                 content and reply markup of the message",
+                RequestErrorKind::MessageNotModified,
+            ),
+            (
+                400,
+                "Bad Request: not enough rights to send text messages to the \
+                 chat",
+                RequestErrorKind::NotEnoughRights,
+            ),
+            (
+                400,
+                "Bad Request: query is too old and response timeout expired \
+                 or query ID is invalid",
+                RequestErrorKind::QueryTooOld,
+            ),
+            (
+                429,
+                "Too Many Requests: retry after 5",
+                RequestErrorKind::TooManyRequests,
+            ),
+            (
+                400,
+                "Bad Request: wrong file identifier/HTTP URL specified",
+                RequestErrorKind::WrongFileId,
+            ),
+            (400, "Bad Request: something new", RequestErrorKind::Other),
+        ];
+
+        for (error_code, description, kind) in &cases {
+            let classified =
+                RequestErrorKind::classify(*error_code, description);
+            assert_eq!(classified, *kind);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use super::MethodCall;
+use is_macro::Is;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+use tokio::time::error::Elapsed;
+
+/// Represents possible errors that may happen while registering a webhook.
+#[derive(Debug, Is)]
+pub enum WebhookSetup {
+    /// Calling the `setWebhook` method resulted in an error.
+    SetWebhook(MethodCall),
+    /// Calling the `setWebhook` method timed out.
+    SetWebhookTimeout(Elapsed),
+    /// Calling the `setMyCommands` method resulted in an error.
+    SetMyCommands(MethodCall),
+    /// Calling the `setMyCommands` method timed out.
+    SetMyCommandsTimeout(Elapsed),
+}
+
+impl Display for WebhookSetup {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::SetWebhook(error) => write!(
+                formatter,
+                "Registering the webhook failed because a call to `setWebhook` \
+                 failed with an error: {}",
+                error,
+            ),
+            Self::SetWebhookTimeout(timeout) => write!(
+                formatter,
+                "Registering the webhook failed because a call to `setWebhook` \
+                 timed out: {}",
+                timeout,
+            ),
+            Self::SetMyCommands(error) => write!(
+                formatter,
+                "Registering the webhook failed because a call to \
+                 `setMyCommands` failed with an error: {}",
+                error,
+            ),
+            Self::SetMyCommandsTimeout(timeout) => write!(
+                formatter,
+                "Registering the webhook failed because a call to \
+                 `setMyCommands` timed out: {}",
+                timeout,
+            ),
+        }
+    }
+}
+
+impl Error for WebhookSetup {}
+
+impl From<MethodCall> for WebhookSetup {
+    #[must_use]
+    fn from(error: MethodCall) -> Self {
+        Self::SetWebhook(error)
+    }
+}
+
+impl From<Elapsed> for WebhookSetup {
+    #[must_use]
+    fn from(error: Elapsed) -> Self {
+        Self::SetWebhookTimeout(error)
+    }
+}
@@ -24,9 +24,11 @@ use type_map::concurrent::TypeMap;
 #[macro_use]
 mod handlers_macros;
 
+mod in_flight;
 mod polling;
 pub mod webhook;
 
+use in_flight::InFlight;
 pub use {polling::Polling, webhook::Webhook};
 
 // Wish trait alises came out soon
@@ -62,6 +64,7 @@ pub struct EventLoop {
     command_description: HashMap<String, String>,
     edited_command_handlers: Map<EditedCommand>,
     update_handlers: TypeMap,
+    in_flight: InFlight,
 }
 
 impl EventLoop {
@@ -73,6 +76,7 @@ impl EventLoop {
             command_description: HashMap::new(),
             edited_command_handlers: HashMap::new(),
             update_handlers: TypeMap::new(),
+            in_flight: InFlight::default(),
         }
     }
 
@@ -129,11 +133,12 @@ impl EventLoop {
         H: Fn(Arc<C>) -> F + Send + Sync + 'static,
         F: Future<Output = ()> + Send + 'static,
     {
+        let in_flight = self.in_flight.clone();
         self.update_handlers
             .entry::<Handlers<C>>()
             .or_insert_with(Vec::new)
             .push(Box::new(move |context| {
-                tokio::spawn(handler(context));
+                in_flight.spawn(handler(context));
             }));
     }
 
@@ -170,11 +175,12 @@ impl EventLoop {
         F: Future<Output = ()> + Send + 'static,
     {
         let command = normalize_cmd_name(command);
+        let in_flight = self.in_flight.clone();
         self.command_handlers
             .entry(command.to_string())
             .or_insert_with(Vec::new)
             .push(Box::new(move |context| {
-                tokio::spawn(handler(context));
+                in_flight.spawn(handler(context));
             }));
     }
 
@@ -226,11 +232,12 @@ impl EventLoop {
         for command in commands {
             let handler = Arc::clone(&handler);
             let command = normalize_cmd_name(command);
+            let in_flight = self.in_flight.clone();
             self.command_handlers
                 .entry(command.to_string())
                 .or_insert_with(Vec::new)
                 .push(Box::new(move |context| {
-                    tokio::spawn(handler(context));
+                    in_flight.spawn(handler(context));
                 }));
         }
     }
@@ -323,11 +330,12 @@ impl EventLoop {
         F: Future<Output = ()> + Send + 'static,
     {
         let command = normalize_cmd_name(command);
+        let in_flight = self.in_flight.clone();
         self.edited_command_handlers
             .entry(command.to_string())
             .or_insert_with(Vec::new)
             .push(Box::new(move |context| {
-                tokio::spawn(handler(context));
+                in_flight.spawn(handler(context));
             }));
     }
 
@@ -346,11 +354,12 @@ impl EventLoop {
         for command in commands {
             let handler = Arc::clone(&handler);
             let command = normalize_cmd_name(command);
+            let in_flight = self.in_flight.clone();
             self.edited_command_handlers
                 .entry(command.to_string())
                 .or_insert_with(Vec::new)
                 .push(Box::new(move |context| {
-                    tokio::spawn(handler(context));
+                    in_flight.spawn(handler(context));
                 }));
         }
     }
@@ -508,17 +517,24 @@ impl EventLoop {
         voice: Voice,
     }
 
-    fn handle_unhandled(&self, update: update::Kind) {
-        let context = Arc::new(Unhandled::new(self.bot.clone(), update));
+    fn handle_unhandled(&self, bot: &Bot, update: update::Kind) {
+        let context = Arc::new(Unhandled::new(bot.clone(), update));
         self.handle(context);
     }
 
-    #[instrument(skip(self, update))]
     fn handle_update(&self, update: types::Update) {
+        self.handle_update_with_bot(&self.bot, update);
+    }
+
+    /// Handles an update passing `bot` to contexts instead of the event loop's
+    /// bot. Used by the webhook server to attach a reply slot to the bot.
+    #[instrument(name = "handle_update", skip(self, bot, update))]
+    fn handle_update_with_bot(&self, bot: &Bot, update: types::Update) {
         trace!(?update);
+        bot.metrics().record_update(update.kind.name());
 
         if self.will_handle::<AnyUpdate>() {
-            let context = AnyUpdate::new(self.bot.clone(), update.clone());
+            let context = AnyUpdate::new(bot.clone(), update.clone());
             self.handle(Arc::new(context));
         }
 
@@ -532,7 +548,7 @@ impl EventLoop {
                     chat_instance,
                 } if self.will_handle::<MessageDataCallback>() => {
                     let context = MessageDataCallback::new(
-                        self.bot.clone(),
+                        bot.clone(),
                         id,
                         from,
                         *message,
@@ -549,7 +565,7 @@ impl EventLoop {
                     chat_instance,
                 } if self.will_handle::<InlineDataCallback>() => {
                     let context = InlineDataCallback::new(
-                        self.bot.clone(),
+                        bot.clone(),
                         id,
                         from,
                         message_id,
@@ -566,7 +582,7 @@ impl EventLoop {
                     chat_instance,
                 } if self.will_handle::<MessageGameCallback>() => {
                     let context = MessageGameCallback::new(
-                        self.bot.clone(),
+                        bot.clone(),
                         id,
                         from,
                         *message,
@@ -583,7 +599,7 @@ impl EventLoop {
                     chat_instance,
                 } if self.will_handle::<InlineGameCallback>() => {
                     let context = InlineGameCallback::new(
-                        self.bot.clone(),
+                        bot.clone(),
                         id,
                         from,
                         message_id,
@@ -594,7 +610,7 @@ impl EventLoop {
                 }
                 query if self.will_handle::<Unhandled>() => {
                     let update = update::Kind::CallbackQuery(query);
-                    self.handle_unhandled(update);
+                    self.handle_unhandled(bot, update);
                 }
                 Query {
                     kind: callback::Kind::Data(..),
@@ -620,59 +636,59 @@ impl EventLoop {
             update::Kind::ChosenInlineResult(result)
                 if self.will_handle::<ChosenInline>() =>
             {
-                let context = ChosenInline::new(self.bot.clone(), result);
+                let context = ChosenInline::new(bot.clone(), result);
                 self.handle(Arc::new(context));
             }
             update::Kind::EditedMessage(message)
             | update::Kind::EditedChannelPost(message) => {
-                self.handle_message_edit_update(message);
+                self.handle_message_edit_update(bot, message);
             }
             update::Kind::InlineQuery(query)
                 if self.will_handle::<Inline>() =>
             {
-                let context = Inline::new(self.bot.clone(), query);
+                let context = Inline::new(bot.clone(), query);
                 self.handle(Arc::new(context));
             }
             update::Kind::Message(message)
             | update::Kind::ChannelPost(message) => {
-                self.handle_message_update(message);
+                self.handle_message_update(bot, message);
             }
             update::Kind::PreCheckoutQuery(query)
                 if self.will_handle::<PreCheckout>() =>
             {
-                let context = PreCheckout::new(self.bot.clone(), query);
+                let context = PreCheckout::new(bot.clone(), query);
                 self.handle(Arc::new(context));
             }
             update::Kind::Poll(poll) if self.will_handle::<UpdatedPoll>() => {
-                let context = UpdatedPoll::new(self.bot.clone(), poll);
+                let context = UpdatedPoll::new(bot.clone(), poll);
                 self.handle(Arc::new(context));
             }
             update::Kind::PollAnswer(answer)
                 if self.will_handle::<PollAnswer>() =>
             {
-                let context = PollAnswer::new(self.bot.clone(), answer);
+                let context = PollAnswer::new(bot.clone(), answer);
                 self.handle(Arc::new(context));
             }
             update::Kind::ShippingQuery(query)
                 if self.will_handle::<Shipping>() =>
             {
-                let context = Shipping::new(self.bot.clone(), query);
+                let context = Shipping::new(bot.clone(), query);
                 self.handle(Arc::new(context));
             }
             update::Kind::ChatMember(update)
                 if self.will_handle::<ChatMember>() =>
             {
-                let context = ChatMember::new(self.bot.clone(), update);
+                let context = ChatMember::new(bot.clone(), update);
                 self.handle(Arc::new(context));
             }
             update::Kind::MyChatMember(update)
                 if self.will_handle::<MyChatMember>() =>
             {
-                let context = MyChatMember::new(self.bot.clone(), update);
+                let context = MyChatMember::new(bot.clone(), update);
                 self.handle(Arc::new(context));
             }
             update if self.will_handle::<Unhandled>() => {
-                self.handle_unhandled(update);
+                self.handle_unhandled(bot, update);
             }
             update::Kind::ChosenInlineResult(..)
             | update::Kind::InlineQuery(..)
@@ -688,7 +704,7 @@ impl EventLoop {
 
     #[allow(clippy::cognitive_complexity)]
     #[allow(clippy::too_many_lines)] // can't split the huge match
-    fn handle_message_update(&self, message: types::Message) {
+    fn handle_message_update(&self, bot: &Bot, message: types::Message) {
         let (data, kind) = message.split();
 
         match kind {
@@ -696,7 +712,7 @@ impl EventLoop {
                 if self.will_handle::<Animation>() =>
             {
                 let context =
-                    Animation::new(self.bot.clone(), data, *animation, caption);
+                    Animation::new(bot.clone(), data, *animation, caption);
                 self.handle(Arc::new(context));
             }
             message::Kind::Audio {
@@ -705,7 +721,7 @@ impl EventLoop {
                 media_group_id,
             } if self.will_handle::<Audio>() => {
                 let context = Audio::new(
-                    self.bot.clone(),
+                    bot.clone(),
                     data,
                     *audio,
                     caption,
@@ -717,30 +733,29 @@ impl EventLoop {
                 if self.will_handle::<ChangedAutoDeleteTimer>() =>
             {
                 let context =
-                    ChangedAutoDeleteTimer::new(self.bot.clone(), data, change);
+                    ChangedAutoDeleteTimer::new(bot.clone(), data, change);
                 self.handle(Arc::new(context));
             }
             message::Kind::ChatPhotoDeleted
                 if self.will_handle::<DeletedChatPhoto>() =>
             {
-                let context = DeletedChatPhoto::new(self.bot.clone(), data);
+                let context = DeletedChatPhoto::new(bot.clone(), data);
                 self.handle(Arc::new(context));
             }
             message::Kind::ConnectedWebsite(website)
                 if self.will_handle::<ConnectedWebsite>() =>
             {
-                let context =
-                    ConnectedWebsite::new(self.bot.clone(), data, website);
+                let context = ConnectedWebsite::new(bot.clone(), data, website);
                 self.handle(Arc::new(context));
             }
             message::Kind::Contact(contact)
                 if self.will_handle::<Contact>() =>
             {
-                let context = Contact::new(self.bot.clone(), data, contact);
+                let context = Contact::new(bot.clone(), data, contact);
                 self.handle(Arc::new(context));
             }
             message::Kind::Dice(dice) if self.will_handle::<Dice>() => {
-                let context = Dice::new(self.bot.clone(), data, dice);
+                let context = Dice::new(bot.clone(), data, dice);
                 self.handle(Arc::new(context));
             }
             message::Kind::Document {
@@ -749,7 +764,7 @@ impl EventLoop {
                 media_group_id,
             } if self.will_handle::<Document>() => {
                 let context = Document::new(
-                    self.bot.clone(),
+                    bot.clone(),
                     data,
                     *document,
                     caption,
@@ -758,63 +773,62 @@ impl EventLoop {
                 self.handle(Arc::new(context));
             }
             message::Kind::Game(game) if self.will_handle::<Game>() => {
-                let context = Game::new(self.bot.clone(), data, *game);
+                let context = Game::new(bot.clone(), data, *game);
                 self.handle(Arc::new(context));
             }
             message::Kind::GroupCreated
                 if self.will_handle::<CreatedGroup>() =>
             {
-                let context = CreatedGroup::new(self.bot.clone(), data);
+                let context = CreatedGroup::new(bot.clone(), data);
                 self.handle(Arc::new(context));
             }
             message::Kind::Invoice(invoice)
                 if self.will_handle::<Invoice>() =>
             {
-                let context = Invoice::new(self.bot.clone(), data, invoice);
+                let context = Invoice::new(bot.clone(), data, invoice);
                 self.handle(Arc::new(context));
             }
             message::Kind::LeftChatMember(member)
                 if self.will_handle::<LeftMember>() =>
             {
-                let context = LeftMember::new(self.bot.clone(), data, member);
+                let context = LeftMember::new(bot.clone(), data, member);
                 self.handle(Arc::new(context));
             }
             message::Kind::Location(location)
                 if self.will_handle::<Location>() =>
             {
-                let context = Location::new(self.bot.clone(), data, location);
+                let context = Location::new(bot.clone(), data, location);
                 self.handle(Arc::new(context));
             }
             message::Kind::MigrateFrom(old_id)
                 if self.will_handle::<Migration>() =>
             {
-                let context = Migration::new(self.bot.clone(), data, old_id);
+                let context = Migration::new(bot.clone(), data, old_id);
                 self.handle(Arc::new(context));
             }
             message::Kind::MigrateTo(..) => (), // ignored on purpose
             message::Kind::NewChatMembers(members)
                 if self.will_handle::<NewMembers>() =>
             {
-                let context = NewMembers::new(self.bot.clone(), data, members);
+                let context = NewMembers::new(bot.clone(), data, members);
                 self.handle(Arc::new(context));
             }
             message::Kind::NewChatPhoto(photo)
                 if self.will_handle::<NewChatPhoto>() =>
             {
-                let context = NewChatPhoto::new(self.bot.clone(), data, photo);
+                let context = NewChatPhoto::new(bot.clone(), data, photo);
                 self.handle(Arc::new(context));
             }
             message::Kind::NewChatTitle(title)
                 if self.will_handle::<NewChatTitle>() =>
             {
-                let context = NewChatTitle::new(self.bot.clone(), data, title);
+                let context = NewChatTitle::new(bot.clone(), data, title);
                 self.handle(Arc::new(context));
             }
             message::Kind::PassportData(passport_data)
                 if self.will_handle::<Passport>() =>
             {
-                let context =
-                    Passport::new(self.bot.clone(), data, passport_data);
+                let context = Passport::new(bot.clone(), data, passport_data);
                 self.handle(Arc::new(context));
             }
             message::Kind::Photo {
@@ -823,7 +837,7 @@ impl EventLoop {
                 media_group_id,
             } if self.will_handle::<Photo>() => {
                 let context = Photo::new(
-                    self.bot.clone(),
+                    bot.clone(),
                     data,
                     photo,
                     caption,
@@ -834,31 +848,29 @@ impl EventLoop {
             message::Kind::Pinned(message)
                 if self.will_handle::<PinnedMessage>() =>
             {
-                let context =
-                    PinnedMessage::new(self.bot.clone(), data, *message);
+                let context = PinnedMessage::new(bot.clone(), data, *message);
                 self.handle(Arc::new(context));
             }
             message::Kind::Poll(poll) if self.will_handle::<Poll>() => {
-                let context = Poll::new(self.bot.clone(), data, poll);
+                let context = Poll::new(bot.clone(), data, poll);
                 self.handle(Arc::new(context));
             }
             message::Kind::ProximityAlert(alert)
                 if self.will_handle::<ProximityAlert>() =>
             {
-                let context =
-                    ProximityAlert::new(self.bot.clone(), data, alert);
+                let context = ProximityAlert::new(bot.clone(), data, alert);
                 self.handle(Arc::new(context));
             }
             message::Kind::Sticker(sticker)
                 if self.will_handle::<Sticker>() =>
             {
-                let context = Sticker::new(self.bot.clone(), data, *sticker);
+                let context = Sticker::new(bot.clone(), data, *sticker);
                 self.handle(Arc::new(context));
             }
             message::Kind::SuccessfulPayment(payment)
                 if self.will_handle::<Payment>() =>
             {
-                let context = Payment::new(self.bot.clone(), data, *payment);
+                let context = Payment::new(bot.clone(), data, *payment);
                 self.handle(Arc::new(context));
             }
             message::Kind::Text(text) if is_command(&text) => {
@@ -870,26 +882,22 @@ impl EventLoop {
 
                 if self.will_handle_command(&command) {
                     let text = trim_command(text);
-                    let context = Command::new(
-                        self.bot.clone(),
-                        data,
-                        text,
-                        command.clone(),
-                    );
+                    let context =
+                        Command::new(bot.clone(), data, text, command.clone());
                     self.handle_command(&command, &Arc::new(context));
                 } else if self.will_handle::<Unhandled>() {
                     let kind = message::Kind::Text(text);
                     let message = Message::new(data, kind);
                     let update = update::Kind::Message(message);
-                    self.handle_unhandled(update);
+                    self.handle_unhandled(bot, update);
                 }
             }
             message::Kind::Text(text) if self.will_handle::<Text>() => {
-                let context = Text::new(self.bot.clone(), data, text);
+                let context = Text::new(bot.clone(), data, text);
                 self.handle(Arc::new(context));
             }
             message::Kind::Venue(venue) if self.will_handle::<Venue>() => {
-                let context = Venue::new(self.bot.clone(), data, venue);
+                let context = Venue::new(bot.clone(), data, venue);
                 self.handle(Arc::new(context));
             }
             message::Kind::Video {
@@ -898,7 +906,7 @@ impl EventLoop {
                 media_group_id,
             } if self.will_handle::<Video>() => {
                 let context = Video::new(
-                    self.bot.clone(),
+                    bot.clone(),
                     data,
                     *video,
                     caption,
@@ -909,29 +917,26 @@ impl EventLoop {
             message::Kind::VideoNote(video_note)
                 if self.will_handle::<VideoNote>() =>
             {
-                let context =
-                    VideoNote::new(self.bot.clone(), data, video_note);
+                let context = VideoNote::new(bot.clone(), data, video_note);
                 self.handle(Arc::new(context));
             }
             message::Kind::Voice { voice, caption }
                 if self.will_handle::<Voice>() =>
             {
-                let context =
-                    Voice::new(self.bot.clone(), data, voice, caption);
+                let context = Voice::new(bot.clone(), data, voice, caption);
                 self.handle(Arc::new(context));
             }
             message::Kind::VoiceChatEnded(ended)
                 if self.will_handle::<EndedVoiceChat>() =>
             {
-                let context =
-                    EndedVoiceChat::new(self.bot.clone(), data, ended);
+                let context = EndedVoiceChat::new(bot.clone(), data, ended);
                 self.handle(Arc::new(context));
             }
             message::Kind::VoiceChatParticipantsInvited(invited)
                 if self.will_handle::<InvitedVoiceChatParticipants>() =>
             {
                 let context = InvitedVoiceChatParticipants::new(
-                    self.bot.clone(),
+                    bot.clone(),
                     data,
                     invited,
                 );
@@ -941,13 +946,13 @@ impl EventLoop {
                 if self.will_handle::<ScheduledVoiceChat>() =>
             {
                 let context =
-                    ScheduledVoiceChat::new(self.bot.clone(), data, scheduled);
+                    ScheduledVoiceChat::new(bot.clone(), data, scheduled);
                 self.handle(Arc::new(context));
             }
             message::Kind::VoiceChatStarted
                 if self.will_handle::<StartedVoiceChat>() =>
             {
-                let context = StartedVoiceChat::new(self.bot.clone(), data);
+                let context = StartedVoiceChat::new(bot.clone(), data);
                 self.handle(Arc::new(context));
             }
             message::Kind::SupergroupCreated
@@ -957,7 +962,7 @@ impl EventLoop {
             kind if self.will_handle::<Unhandled>() => {
                 let message = Message::new(data, kind);
                 let update = update::Kind::Message(message);
-                self.handle_unhandled(update);
+                self.handle_unhandled(bot, update);
             }
             message::Kind::Animation { .. }
             | message::Kind::Audio { .. }
@@ -997,7 +1002,7 @@ impl EventLoop {
     }
 
     #[allow(clippy::too_many_lines, clippy::cognitive_complexity)] // can't split the huge match
-    fn handle_message_edit_update(&self, message: types::Message) {
+    fn handle_message_edit_update(&self, bot: &Bot, message: types::Message) {
         let (data, kind) = message.split();
         let edit_date = if let Some(edit_date) = data.edit_date {
             edit_date
@@ -1011,7 +1016,7 @@ impl EventLoop {
                 if self.will_handle::<EditedAnimation>() =>
             {
                 let context = EditedAnimation::new(
-                    self.bot.clone(),
+                    bot.clone(),
                     data,
                     edit_date,
                     *animation,
@@ -1025,7 +1030,7 @@ impl EventLoop {
                 media_group_id,
             } if self.will_handle::<EditedAudio>() => {
                 let context = EditedAudio::new(
-                    self.bot.clone(),
+                    bot.clone(),
                     data,
                     edit_date,
                     *audio,
@@ -1040,7 +1045,7 @@ impl EventLoop {
                 media_group_id,
             } if self.will_handle::<EditedDocument>() => {
                 let context = EditedDocument::new(
-                    self.bot.clone(),
+                    bot.clone(),
                     data,
                     edit_date,
                     *document,
@@ -1052,12 +1057,8 @@ impl EventLoop {
             message::Kind::Location(location)
                 if self.will_handle::<EditedLocation>() =>
             {
-                let context = EditedLocation::new(
-                    self.bot.clone(),
-                    data,
-                    edit_date,
-                    location,
-                );
+                let context =
+                    EditedLocation::new(bot.clone(), data, edit_date, location);
                 self.handle(Arc::new(context));
             }
             message::Kind::Photo {
@@ -1066,7 +1067,7 @@ impl EventLoop {
                 media_group_id,
             } if self.will_handle::<EditedPhoto>() => {
                 let context = EditedPhoto::new(
-                    self.bot.clone(),
+                    bot.clone(),
                     data,
                     edit_date,
                     photo,
@@ -1084,7 +1085,7 @@ impl EventLoop {
                 if self.will_handle_edited_command(&command) {
                     let text = trim_command(text);
                     let context = EditedCommand::new(
-                        self.bot.clone(),
+                        bot.clone(),
                         data,
                         edit_date,
                         text,
@@ -1095,12 +1096,12 @@ impl EventLoop {
                     let kind = message::Kind::Text(text);
                     let message = Message::new(data, kind);
                     let update = update::Kind::EditedMessage(message);
-                    self.handle_unhandled(update);
+                    self.handle_unhandled(bot, update);
                 }
             }
             message::Kind::Text(text) if self.will_handle::<EditedText>() => {
                 let context =
-                    EditedText::new(self.bot.clone(), data, edit_date, text);
+                    EditedText::new(bot.clone(), data, edit_date, text);
                 self.handle(Arc::new(context));
             }
             message::Kind::Video {
@@ -1109,7 +1110,7 @@ impl EventLoop {
                 media_group_id,
             } if self.will_handle::<EditedVideo>() => {
                 let context = EditedVideo::new(
-                    self.bot.clone(),
+                    bot.clone(),
                     data,
                     edit_date,
                     *video,
@@ -1155,7 +1156,7 @@ impl EventLoop {
             kind if self.will_handle::<Unhandled>() => {
                 let message = Message::new(data, kind);
                 let update = update::Kind::EditedMessage(message);
-                self.handle_unhandled(update)
+                self.handle_unhandled(bot, update)
             }
             message::Kind::Animation { .. }
             | message::Kind::Audio { .. }
@@ -0,0 +1,43 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Counts handlers that are currently running.
+#[derive(Debug, Clone, Default)]
+pub struct InFlight(Arc<AtomicUsize>);
+
+/// Decrements the counter once a handler finishes, even if it panics.
+pub struct Guard(InFlight);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        (self.0).0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl InFlight {
+    pub fn spawn<F>(&self, handler: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let guard = self.enter();
+        tokio::spawn(async move {
+            let _guard = guard;
+            handler.await;
+        });
+    }
+
+    /// Counts a handler as running until the guard is dropped.
+    pub fn enter(&self) -> Guard {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        Guard(self.clone())
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
@@ -92,9 +92,9 @@ impl Polling {
     }
 
     /// Configures for how long `tbot` should wait for `getUpdates`. If this
-    /// timeout is exceeded, the [error handler] is triggered. If you don't
-    /// configure this value, it is set to
-    /// `Duration::from_secs(timeout.unwrap_or(0) + 60)`.
+    /// timeout is exceeded, the [error handler] is triggered with
+    /// [`errors::MethodCall::Timeout`]. If you don't configure this value,
+    /// it is set to `Duration::from_secs(timeout.unwrap_or(0) + 60)`.
     ///
     /// [error handler]: Self::error_handler
     pub const fn request_timeout(mut self, timeout: Duration) -> Self {
@@ -156,30 +156,35 @@ impl Polling {
             let get_updates = event_loop
                 .bot
                 .get_updates(offset, limit, timeout, allowed_updates)
+                .without_retries()
+                .timeout(request_timeout)
                 .call();
 
-            match timeout_future(request_timeout, get_updates).await {
-                Ok(Ok(raw_updates)) => {
+            match get_updates.await {
+                Ok(raw_updates) => {
                     if let Some(update) = raw_updates.last() {
                         offset = Some(update.id.0 + 1);
                     }
 
                     for raw_update in raw_updates {
                         match raw_update.try_into() {
-                            Ok(update) => event_loop
-                                .handle_update(update),
-                            Err(error) => eprintln!(
-                                "[tbot] Failed to parse an update: {:?}. `tbot` \
-                                 will skip it, but this error means that \
-                                 `tbot`'s type deserialization doesn't match \
-                                 the Bot API. You should file an issue at \
-                                 https://gitlab.com/SnejUgal/tbot.",
-                                error
-                            ),
+                            Ok(update) => event_loop.handle_update(update),
+                            Err(error) => {
+                                event_loop.bot.metrics().record_parse_failure();
+                                eprintln!(
+                                    "[tbot] Failed to parse an update: {:?}. \
+                                     `tbot` will skip it, but this error means \
+                                     that `tbot`'s type deserialization \
+                                     doesn't match the Bot API. You should \
+                                     file an issue at \
+                                     https://gitlab.com/SnejUgal/tbot.",
+                                    error
+                                );
+                            }
                         }
                     }
                 }
-                Ok(Err(error)) => {
+                Err(error) => {
                     if let errors::MethodCall::RequestError {
                         retry_after: Some(retry_after),
                         ..
@@ -190,7 +195,6 @@ impl Polling {
 
                     error_handler(error.into());
                 }
-                Err(error) => error_handler(error.into()),
             }
 
             next_tick.await
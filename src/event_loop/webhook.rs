@@ -1,34 +1,45 @@
 //! Types related to the webhook event loop.
 
 use super::EventLoop;
-use crate::types::parameters::AllowedUpdates;
-use hyper::{
-    body::{Body, HttpBody},
-    Method, Request, Response,
-};
+use crate::{errors, types::parameters::AllowedUpdates};
 use std::{
     net::{IpAddr, Ipv4Addr},
-    num::NonZeroU32,
-    sync::Arc,
+    num::{NonZeroU32, NonZeroUsize},
     time::Duration,
 };
+use tokio::time::{sleep, timeout};
+use tracing::{error, instrument};
 
 mod http;
 pub mod https;
+pub(crate) mod reply;
+mod service;
 
 pub use http::Http;
 pub use https::Https;
+pub use service::Service;
+
+/// How long a server waits after failing to accept a connection, like
+/// `hyper`'s server does.
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_secs(1);
+
+/// Logs a failure to accept a connection and waits before accepting the next
+/// one, so that transient errors like running out of file descriptors neither
+/// shut the server down nor make it spin.
+async fn on_accept_error(error: std::io::Error) {
+    error!(%error, "Failed to accept a connection");
+    sleep(ACCEPT_ERROR_DELAY).await;
+}
 
 /// Configures webhook and starts a server.
 ///
 /// To construct a `Webhook`, use [`EventLoop::webhook`].
 #[must_use = "webhook does not start unless `start` is called"]
 pub struct Webhook<'a> {
-    event_loop: EventLoop,
+    service: Service,
     bind_to: IpAddr,
     port: u16,
     request_timeout: Duration,
-    updates_url: String,
 
     url: &'a str,
     ip_address: Option<IpAddr>,
@@ -41,11 +52,10 @@ pub struct Webhook<'a> {
 impl<'a> Webhook<'a> {
     pub(crate) fn new(event_loop: EventLoop, url: &'a str, port: u16) -> Self {
         Self {
-            event_loop,
+            service: Service::new(event_loop),
             bind_to: IpAddr::V4(Ipv4Addr::LOCALHOST),
             port,
             request_timeout: Duration::from_secs(60),
-            updates_url: String::from("/"),
 
             url,
             ip_address: None,
@@ -76,7 +86,7 @@ impl<'a> Webhook<'a> {
             );
         }
 
-        self.updates_url = url;
+        self.service = self.service.accept_updates_on(url);
         self
     }
 
@@ -118,6 +128,58 @@ impl<'a> Webhook<'a> {
         self
     }
 
+    /// Allows handlers to send a method in the response to the webhook
+    /// request using [`Bot::reply_via_webhook`]. The response is held until
+    /// a handler sends a method, all handlers drop their contexts, or
+    /// `timeout` passes.
+    ///
+    /// Keep `timeout` short: the Bot API server does not send the next update
+    /// until it gets the response.
+    ///
+    /// [`Bot::reply_via_webhook`]: crate::Bot::reply_via_webhook
+    pub fn allow_replies(mut self, timeout: Duration) -> Self {
+        self.service = self.service.allow_replies(timeout);
+        self
+    }
+
+    /// Configures the maximum size of a request's body. If a request is larger,
+    /// `tbot` responds with `413 Payload Too Large`. 1 megabyte by default.
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.service = self.service.max_body_size(size);
+        self
+    }
+
+    /// Configures how many handlers may run at the same time. While this
+    /// limit is reached, `tbot` responds with `503 Service Unavailable` and
+    /// the Bot API server will deliver the update later. Not limited
+    /// by default.
+    pub fn max_running_handlers(mut self, limit: NonZeroUsize) -> Self {
+        self.service = self.service.max_running_handlers(limit);
+        self
+    }
+
+    /// Enables a health check route on the provided URL, usually `/healthz`.
+    /// See [`Service::health_check_on`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URL doesn't starts with `/`.
+    pub fn health_check_on(mut self, url: String) -> Self {
+        self.service = self.service.health_check_on(url);
+        self
+    }
+
+    /// Enables a metrics route on the provided URL, usually `/metrics`.
+    /// See [`Service::metrics_on`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URL doesn't starts with `/`.
+    pub fn metrics_on(mut self, url: String) -> Self {
+        self.service = self.service.metrics_on(url);
+        self
+    }
+
     /// Configures a webhook server over HTTP. For HTTPS, see the [`https`]
     /// method.
     ///
@@ -143,44 +205,73 @@ impl<'a> Webhook<'a> {
             config,
         )
     }
-}
 
-fn is_request_correct(request: &Request<Body>, updates_url: &str) -> bool {
-    let content_type = request.headers().get("Content-Type");
+    /// Configures a webhook server over HTTPS with a certificate and a private
+    /// key read from disk. Unlike [`https`], the server picks up a renewed
+    /// certificate without restarting, see [`https::CertificateFiles`].
+    ///
+    /// [`https`]: Self::https
+    pub const fn https_from_files(
+        self,
+        files: https::CertificateFiles,
+    ) -> Https<'a> {
+        Https::from_files(self, files)
+    }
 
-    request.method() == Method::POST
-        && request.uri() == updates_url
-        && content_type.map(|x| x == "application/json") == Some(true)
-}
+    /// Calls `setWebhook` and `setMyCommands` without starting a server, and
+    /// returns a [`Service`] that handles updates.
+    ///
+    /// Use this method if you want to mount the bot on your own server instead
+    /// of starting one with [`http`] or [`https`]. Note that `bind_to` and
+    /// `port` are not used in this case; you're responsible for serving
+    /// the returned service so that the Bot API server can reach it at `url`.
+    ///
+    /// [`http`]: Self::http
+    /// [`https`]: Self::https
+    #[instrument(name = "webhook_register", skip(self))]
+    pub async fn register(self) -> Result<Service, errors::WebhookSetup> {
+        self.set_webhook(self.certificate, self.drop_pending_updates)
+            .await?;
+        self.set_commands().await?;
 
-async fn handle(
-    event_loop: Arc<EventLoop>,
-    request: Request<Body>,
-    updates_url: Arc<String>,
-) -> Result<Response<Body>, hyper::Error> {
-    if is_request_correct(&request, &*updates_url) {
-        let (parts, mut body) = request.into_parts();
-        let mut request = parts
-            .headers
-            .get("Content-Length")
-            .and_then(|x| x.to_str().ok().and_then(|x| x.parse().ok()))
-            .map_or_else(Vec::new, Vec::with_capacity);
-
-        while let Some(chunk) = body.data().await {
-            request.extend(chunk?);
-        }
+        Ok(self.service)
+    }
 
-        match serde_json::from_slice(&request[..]) {
-            Ok(update) => event_loop.handle_update(update),
-            Err(error) => eprintln!(
-                "[tbot] Failed to parse an update: {:?}. `tbot` will skip it, \
-                 but this error means that `tbot`'s type deserialization \
-                 doesn't match the Bot API. You should file an issue at \
-                 https://gitlab.com/SnejUgal/tbot.",
-                error
-            ),
-        }
+    pub(crate) async fn set_webhook(
+        &self,
+        certificate: Option<&str>,
+        drop_pending_updates: bool,
+    ) -> Result<(), errors::WebhookSetup> {
+        let set_webhook = self
+            .service
+            .event_loop
+            .bot
+            .set_webhook(
+                self.url,
+                self.ip_address,
+                certificate,
+                self.max_connections,
+                self.allowed_updates,
+                drop_pending_updates,
+            )
+            .call();
+
+        timeout(self.request_timeout, set_webhook).await??;
+
+        Ok(())
     }
 
-    Ok(Response::new(Body::empty()))
+    pub(crate) async fn set_commands(
+        &self,
+    ) -> Result<(), errors::WebhookSetup> {
+        let set_commands = self.service.event_loop.set_commands_descriptions();
+
+        match timeout(self.request_timeout, set_commands).await {
+            Ok(Err(method)) => Err(errors::WebhookSetup::SetMyCommands(method)),
+            Err(timeout) => {
+                Err(errors::WebhookSetup::SetMyCommandsTimeout(timeout))
+            }
+            Ok(Ok(())) => Ok(()),
+        }
+    }
 }
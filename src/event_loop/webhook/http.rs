@@ -1,22 +1,71 @@
-use super::handle;
 use crate::{errors, event_loop::Webhook};
-use hyper::{
-    service::{make_service_fn, service_fn},
-    Server,
-};
-use std::{convert::Infallible, net::SocketAddr, sync::Arc};
-use tokio::time::timeout;
+use hyper::{service::make_service_fn, Server};
+use std::{convert::Infallible, net::SocketAddr};
 use tracing::instrument;
 
+#[cfg(unix)]
+use super::on_accept_error;
+#[cfg(unix)]
+use futures::stream;
+#[cfg(unix)]
+use hyper::server::accept;
+#[cfg(unix)]
+use std::{
+    fs::Permissions,
+    io::ErrorKind,
+    os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+#[cfg(unix)]
+use tokio::{fs, net::UnixListener};
+
 /// Configures the HTTP webhook server.
 #[must_use = "webhook server needs to be `start`ed to run the event loop"]
 pub struct Http<'a> {
     webhook: Webhook<'a>,
+    #[cfg(unix)]
+    unix_socket: Option<PathBuf>,
+    #[cfg(unix)]
+    unix_socket_mode: Option<u32>,
 }
 
 impl<'a> Http<'a> {
     pub(crate) const fn new(webhook: Webhook<'a>) -> Self {
-        Self { webhook }
+        Self {
+            webhook,
+            #[cfg(unix)]
+            unix_socket: None,
+            #[cfg(unix)]
+            unix_socket_mode: None,
+        }
+    }
+
+    /// Configures the server to listen on a Unix domain socket instead of
+    /// a TCP port, e.g. if the bot runs behind a reverse proxy on the same
+    /// host. `bind_to` and `port` are not used in this case, while the URL
+    /// passed to `setWebhook` stays the same.
+    ///
+    /// If a socket already exists at `path` and no server accepts connections
+    /// on it, `tbot` considers it a stale socket left after a previous run
+    /// and removes it. If a file other than a socket exists at `path`, `start`
+    /// fails instead. `tbot` removes the socket if the server fails, but as
+    /// the server otherwise runs until the process exits, the socket is
+    /// usually left behind and removed on the next start.
+    #[cfg(unix)]
+    pub fn unix_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.unix_socket = Some(path.into());
+        self
+    }
+
+    /// Configures the permissions of the Unix domain socket, e.g. `0o660` to
+    /// let only the reverse proxy in the same group connect to the socket.
+    /// The permissions are set before the socket appears at its path, so no
+    /// one else can connect in the meantime. If not configured,
+    /// the permissions depend on the process's `umask`.
+    #[cfg(unix)]
+    pub const fn unix_socket_mode(mut self, mode: u32) -> Self {
+        self.unix_socket_mode = Some(mode);
+        self
     }
 }
 
@@ -24,68 +73,132 @@ impl<'a> Http<'a> {
     /// Starts the server.
     #[instrument(name = "http_webhook", skip(self))]
     pub async fn start(self) -> Result<Infallible, errors::HttpWebhook> {
-        let Webhook {
-            event_loop,
-            bind_to,
-            port,
-            updates_url,
-            url,
-            ip_address,
-            certificate,
-            max_connections,
-            allowed_updates,
-            request_timeout,
-            drop_pending_updates,
-        } = self.webhook;
-
-        let set_webhook = event_loop
-            .bot
-            .set_webhook(
-                url,
-                ip_address,
-                certificate,
-                max_connections,
-                allowed_updates,
-                drop_pending_updates,
-            )
-            .call();
-
-        timeout(request_timeout, set_webhook).await??;
-
-        let set_commands = event_loop.set_commands_descriptions();
-        match timeout(request_timeout, set_commands).await {
-            Ok(Err(method)) => {
-                return Err(errors::HttpWebhook::SetMyCommands(method))
-            }
-            Err(timeout) => {
-                return Err(errors::HttpWebhook::SetMyCommandsTimeout(timeout))
-            }
-            Ok(_) => (),
-        };
+        let addr = SocketAddr::new(self.webhook.bind_to, self.webhook.port);
+        let service = self.webhook.register().await?;
+
+        #[cfg(unix)]
+        if let Some(path) = self.unix_socket {
+            let listener =
+                bind_unix_socket(&path, self.unix_socket_mode).await?;
+
+            let incoming = stream::unfold(listener, |listener| async move {
+                loop {
+                    match listener.accept().await {
+                        Ok((stream, _)) => {
+                            return Some((
+                                Ok::<_, Infallible>(stream),
+                                listener,
+                            ))
+                        }
+                        Err(error) => on_accept_error(error).await,
+                    }
+                }
+            });
+            let result = Server::builder(accept::from_stream(incoming))
+                .serve(make_service_fn(move |_| {
+                    let service = service.clone();
+                    async move { Ok::<_, hyper::Error>(service) }
+                }))
+                .await;
+            let _ = fs::remove_file(&path).await;
+            result?;
 
-        let event_loop = Arc::new(event_loop);
-        let addr = SocketAddr::new(bind_to, port);
-        let updates_url = Arc::new(updates_url);
+            unreachable!("[tbot] The webhook server unexpectedly returned.");
+        }
 
         Server::bind(&addr)
             .serve(make_service_fn(move |_| {
-                let event_loop = Arc::clone(&event_loop);
-                let updates_url = Arc::clone(&updates_url);
-
-                async move {
-                    let service = service_fn(move |request| {
-                        handle(
-                            Arc::clone(&event_loop),
-                            request,
-                            Arc::clone(&updates_url),
-                        )
-                    });
-
-                    Ok::<_, hyper::Error>(service)
-                }
+                let service = service.clone();
+                async move { Ok::<_, hyper::Error>(service) }
             }))
             .await?;
 
         unreachable!("[tbot] The webhook server unexpectedly returned.");
     }
 }
+
+/// Binds to a Unix domain socket, removing a stale socket file if needed.
+#[cfg(unix)]
+async fn bind_unix_socket(
+    path: &Path,
+    mode: Option<u32>,
+) -> std::io::Result<UnixListener> {
+    if let Ok(metadata) = fs::symlink_metadata(path).await {
+        if !metadata.file_type().is_socket() {
+            return Err(std::io::Error::new(
+                ErrorKind::AddrInUse,
+                "a file which isn't a socket exists at the socket's path",
+            ));
+        }
+
+        match tokio::net::UnixStream::connect(path).await {
+            Ok(_) => {
+                return Err(std::io::Error::new(
+                    ErrorKind::AddrInUse,
+                    "another server is listening on the socket",
+                ))
+            }
+            Err(error) if error.kind() == ErrorKind::ConnectionRefused => {
+                fs::remove_file(path).await?;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    let mode = match mode {
+        Some(mode) => mode,
+        None => return UnixListener::bind(path),
+    };
+
+    // The socket is created in a directory only we can access, so that no one
+    // can connect to it before its permissions are set.
+    let mut private_dir = path.as_os_str().to_owned();
+    private_dir.push(format!(".{}.tmp", std::process::id()));
+    let private_dir = PathBuf::from(private_dir);
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)?;
+
+    let private_path = private_dir.join("socket");
+    let result = async {
+        let listener = UnixListener::bind(&private_path)?;
+        fs::set_permissions(&private_path, Permissions::from_mode(mode))
+            .await?;
+        fs::rename(&private_path, path).await?;
+        Ok(listener)
+    }
+    .await;
+
+    let _ = fs::remove_file(&private_path).await;
+    let _ = fs::remove_dir(&private_dir).await;
+    result
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn binds_unix_sockets_safely() {
+        let dir = std::env::temp_dir()
+            .join(format!("tbot-unix-socket-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("webhook.sock");
+
+        std::fs::write(&path, "not a socket").unwrap();
+        let error = bind_unix_socket(&path, None).await.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::AddrInUse);
+        assert_eq!(std::fs::read(&path).unwrap(), b"not a socket");
+        std::fs::remove_file(&path).unwrap();
+
+        let listener = bind_unix_socket(&path, Some(0o600)).await.unwrap();
+        let metadata = std::fs::symlink_metadata(&path).unwrap();
+        assert!(metadata.file_type().is_socket());
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+
+        drop(listener);
+        let listener = bind_unix_socket(&path, None).await.unwrap();
+        drop(listener);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
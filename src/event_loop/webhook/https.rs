@@ -1,31 +1,199 @@
 //! Types related to the HTTPS webhook server.
 
-use super::handle;
+use super::on_accept_error;
 use crate::{errors, event_loop::Webhook};
-use hyper::{server::conn::Http, service::service_fn};
-use hyper::{Body, Request};
-use tracing::instrument;
+use futures::future::{select, Either};
+use hyper::server::conn::Http;
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{PoisonError, RwLock},
+    time::{Duration, SystemTime},
+};
+use tokio::{fs, net::TcpListener, time::interval};
+use tracing::{error, info, instrument, warn};
 
 #[cfg(feature = "tls")]
 pub use native_tls::Identity;
 #[cfg(feature = "tls")]
 use native_tls::TlsAcceptor;
-use std::{convert::Infallible, net::SocketAddr, sync::Arc};
-use tokio::net::TcpListener;
-use tokio::time::timeout;
+#[cfg(feature = "rustls")]
+use std::sync::Arc;
 #[cfg(feature = "rustls")]
 pub use tokio_rustls::rustls::ServerConfig;
 #[cfg(feature = "rustls")]
-use tokio_rustls::TlsAcceptor;
+use tokio_rustls::{
+    rustls::{
+        internal::pemfile::{certs, pkcs8_private_keys, rsa_private_keys},
+        NoClientAuth, TLSError,
+    },
+    TlsAcceptor,
+};
+
+#[cfg(feature = "tls")]
+type Acceptor = tokio_native_tls::TlsAcceptor;
+#[cfg(feature = "rustls")]
+type Acceptor = TlsAcceptor;
+
+/// A certificate and a private key which are read from disk and re-read
+/// when they change.
+///
+/// With `CertificateFiles`, you can renew your certificate (e.g. with
+/// `certbot`) without restarting the bot: `tbot` checks the files'
+/// modification time every [`check_interval`] and, if either file changed,
+/// uses the new certificate for new connections. Connections which are
+/// already established are not dropped.
+///
+/// Both files must be in the PEM format, and the private key must be
+/// a PKCS #8 key (or an RSA key if you use `rustls`).
+///
+/// [`check_interval`]: Self::check_interval
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct CertificateFiles {
+    certificate: PathBuf,
+    private_key: PathBuf,
+    check_interval: Duration,
+    reupload: bool,
+}
+
+impl CertificateFiles {
+    /// Constructs new `CertificateFiles`.
+    pub fn new(
+        certificate: impl Into<PathBuf>,
+        private_key: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            certificate: certificate.into(),
+            private_key: private_key.into(),
+            check_interval: Duration::from_secs(60),
+            reupload: false,
+        }
+    }
+
+    /// Configures how often `tbot` checks whether the files changed.
+    /// `60s` by default.
+    pub const fn check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Configures `tbot` to upload the certificate with `setWebhook` on start
+    /// and each time it's reloaded. You need this if your certificate is
+    /// self-signed.
+    pub const fn reupload_certificate(mut self) -> Self {
+        self.reupload = true;
+        self
+    }
+
+    async fn modified(&self) -> std::io::Result<(SystemTime, SystemTime)> {
+        let certificate = fs::metadata(&self.certificate).await?.modified()?;
+        let private_key = fs::metadata(&self.private_key).await?.modified()?;
+
+        Ok((certificate, private_key))
+    }
+
+    /// Reloads the files if they changed since `last_modified`. If they fail
+    /// to load, `last_modified` is kept so that they're reloaded on the next
+    /// check.
+    async fn reload(
+        &self,
+        last_modified: &mut Option<(SystemTime, SystemTime)>,
+    ) -> Option<(Acceptor, String)> {
+        let modified = match self.modified().await {
+            Ok(modified) => modified,
+            Err(error) => {
+                error!(%error, "Failed to check the certificate files");
+                return None;
+            }
+        };
+
+        if *last_modified == Some(modified) {
+            return None;
+        }
+
+        match self.load().await {
+            Ok(loaded) => {
+                *last_modified = Some(modified);
+                Some(loaded)
+            }
+            Err(error) => {
+                error!(%error, "Failed to reload the certificate");
+                None
+            }
+        }
+    }
+
+    /// Reads the files and returns the acceptor along with the certificate.
+    async fn load(&self) -> Result<(Acceptor, String), errors::HttpsWebhook> {
+        let certificate = fs::read(&self.certificate)
+            .await
+            .map_err(errors::HttpsWebhook::Certificate)?;
+        let private_key = fs::read(&self.private_key)
+            .await
+            .map_err(errors::HttpsWebhook::Certificate)?;
+
+        #[cfg(feature = "tls")]
+        let acceptor = {
+            let identity = Identity::from_pkcs8(&certificate, &private_key)?;
+            acceptor(identity)?
+        };
+
+        #[cfg(feature = "rustls")]
+        let acceptor = {
+            let chain = certs(&mut &certificate[..]).map_err(|()| {
+                TLSError::General(String::from("Failed to parse certificate"))
+            })?;
+            let mut keys =
+                pkcs8_private_keys(&mut &private_key[..]).map_err(|()| {
+                    TLSError::General(String::from("Failed to parse key"))
+                })?;
+            if keys.is_empty() {
+                keys =
+                    rsa_private_keys(&mut &private_key[..]).map_err(|()| {
+                        TLSError::General(String::from("Failed to parse key"))
+                    })?;
+            }
+            let key = keys.into_iter().next().ok_or_else(|| {
+                TLSError::General(String::from("No private key found"))
+            })?;
+
+            let mut config = ServerConfig::new(NoClientAuth::new());
+            config.set_single_cert(chain, key)?;
+            acceptor(config)
+        };
+
+        let certificate = String::from_utf8_lossy(&certificate).into_owned();
+
+        Ok((acceptor, certificate))
+    }
+}
+
+enum Source {
+    #[cfg(feature = "tls")]
+    Identity(Identity),
+    #[cfg(feature = "rustls")]
+    Config(ServerConfig),
+    Files(CertificateFiles),
+}
+
+#[cfg(feature = "tls")]
+fn acceptor(identity: Identity) -> Result<Acceptor, native_tls::Error> {
+    let tls_acceptor = TlsAcceptor::builder(identity).build()?;
+    Ok(tokio_native_tls::TlsAcceptor::from(tls_acceptor))
+}
+
+#[cfg(feature = "rustls")]
+fn acceptor(config: ServerConfig) -> Acceptor {
+    TlsAcceptor::from(Arc::new(config))
+}
 
 /// Configures the HTTPS webhook server.
 #[must_use = "webhook server needs to be `start`ed to run the event loop"]
 pub struct Https<'a> {
     webhook: Webhook<'a>,
-    #[cfg(feature = "tls")]
-    identity: Identity,
-    #[cfg(feature = "rustls")]
-    config: ServerConfig,
+    source: Source,
 }
 
 impl<'a> Https<'a> {
@@ -37,9 +205,19 @@ impl<'a> Https<'a> {
         Self {
             webhook,
             #[cfg(feature = "tls")]
-            identity,
+            source: Source::Identity(identity),
             #[cfg(feature = "rustls")]
-            config,
+            source: Source::Config(config),
+        }
+    }
+
+    pub(crate) const fn from_files(
+        webhook: Webhook<'a>,
+        files: CertificateFiles,
+    ) -> Self {
+        Self {
+            webhook,
+            source: Source::Files(files),
         }
     }
 }
@@ -48,79 +226,167 @@ impl<'a> Https<'a> {
     /// Starts the event loop.
     #[instrument(name = "https_webhook", skip(self))]
     pub async fn start(self) -> Result<Infallible, errors::HttpsWebhook> {
-        let Webhook {
-            event_loop,
-            bind_to,
-            port,
-            updates_url,
-            url,
-            ip_address,
-            certificate,
-            max_connections,
-            allowed_updates,
-            request_timeout,
-            drop_pending_updates,
-        } = self.webhook;
-
-        let set_webhook = event_loop
-            .bot
-            .set_webhook(
-                url,
-                ip_address,
-                certificate,
-                max_connections,
-                allowed_updates,
-                drop_pending_updates,
-            )
-            .call();
-
-        timeout(request_timeout, set_webhook).await??;
-
-        let set_commands = event_loop.set_commands_descriptions();
-        match timeout(request_timeout, set_commands).await {
-            Ok(Err(method)) => {
-                return Err(errors::HttpsWebhook::SetMyCommands(method))
-            }
-            Err(timeout) => {
-                return Err(errors::HttpsWebhook::SetMyCommandsTimeout(timeout))
+        let Self { webhook, source } = self;
+        let addr = SocketAddr::new(webhook.bind_to, webhook.port);
+
+        let (tls_acceptor, certificate, files) = match source {
+            #[cfg(feature = "tls")]
+            Source::Identity(identity) => (acceptor(identity)?, None, None),
+            #[cfg(feature = "rustls")]
+            Source::Config(config) => (acceptor(config), None, None),
+            Source::Files(files) => {
+                let (tls_acceptor, certificate) = files.load().await?;
+                let certificate = Some(certificate).filter(|_| files.reupload);
+
+                (tls_acceptor, certificate, Some(files))
             }
-            Ok(_) => (),
         };
 
-        let event_loop = Arc::new(event_loop);
-        let addr = SocketAddr::new(bind_to, port);
-        let updates_url = Arc::new(updates_url);
+        let certificate = certificate.as_deref().or(webhook.certificate);
+        webhook
+            .set_webhook(certificate, webhook.drop_pending_updates)
+            .await?;
+        webhook.set_commands().await?;
 
-        #[cfg(feature = "tls")]
-        let tls_acceptor = {
-            let tls_acceptor = TlsAcceptor::builder(self.identity).build()?;
-            tokio_native_tls::TlsAcceptor::from(tls_acceptor)
+        let tls_acceptor = RwLock::new(tls_acceptor);
+        let server = TcpListener::bind(&addr).await?;
+        let serve = Box::pin(serve(&server, &tls_acceptor, &webhook));
+
+        let never = match files {
+            Some(files) => {
+                let watch = Box::pin(watch(&files, &tls_acceptor, &webhook));
+                match select(serve, watch).await {
+                    Either::Left((never, _)) | Either::Right((never, _)) => {
+                        never
+                    }
+                }
+            }
+            None => serve.await,
         };
-        #[cfg(feature = "rustls")]
-        let tls_acceptor = TlsAcceptor::from(Arc::new(self.config));
 
-        let server = TcpListener::bind(&addr).await?;
+        match never {}
+    }
+}
 
-        let http_proto = Http::new();
+/// Accepts connections and serves each one in its own task, so that a slow
+/// client doesn't hold up others. Failed handshakes and connections are only
+/// logged, as they don't affect other clients.
+async fn serve(
+    server: &TcpListener,
+    tls_acceptor: &RwLock<Acceptor>,
+    webhook: &Webhook<'_>,
+) -> Infallible {
+    let http_proto = Http::new();
 
-        loop {
-            let (tcp_stream, _) = server.accept().await?;
-            let tls_stream = tls_acceptor.accept(tcp_stream).await?;
+    loop {
+        let (tcp_stream, peer) = match server.accept().await {
+            Ok(accepted) => accepted,
+            Err(error) => {
+                on_accept_error(error).await;
+                continue;
+            }
+        };
 
-            let event_loop = Arc::clone(&event_loop);
-            let updates_url = Arc::clone(&updates_url);
+        let tls_acceptor = tls_acceptor
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let http_proto = http_proto.clone();
+        let service = webhook.service.clone();
 
-            let service = service_fn(move |request: Request<Body>| {
-                handle(
-                    Arc::clone(&event_loop),
-                    request,
-                    Arc::clone(&updates_url),
-                )
-            });
+        tokio::spawn(async move {
+            let tls_stream = match tls_acceptor.accept(tcp_stream).await {
+                Ok(tls_stream) => tls_stream,
+                Err(error) => {
+                    warn!(%peer, %error, "TLS handshake failed");
+                    return;
+                }
+            };
 
             let conn = http_proto.serve_connection(tls_stream, service);
+            if let Err(error) = conn.await {
+                warn!(%peer, %error, "Failed to serve a connection");
+            }
+        });
+    }
+}
+
+/// Checks if the certificate files changed and reloads them.
+async fn watch(
+    files: &CertificateFiles,
+    tls_acceptor: &RwLock<Acceptor>,
+    webhook: &Webhook<'_>,
+) -> Infallible {
+    let mut last_modified = files.modified().await.ok();
+    let mut interval = interval(files.check_interval);
+
+    loop {
+        interval.tick().await;
+
+        let (new_acceptor, certificate) =
+            match files.reload(&mut last_modified).await {
+                Some(reloaded) => reloaded,
+                None => continue,
+            };
+
+        *tls_acceptor.write().unwrap_or_else(PoisonError::into_inner) =
+            new_acceptor;
+        info!("Reloaded the certificate");
+
+        if files.reupload {
+            let set_webhook = webhook.set_webhook(Some(&certificate), false);
 
-            conn.await?;
+            if let Err(error) = set_webhook.await {
+                error!(%error, "Failed to re-upload the certificate");
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    const FIRST_CERTIFICATE: &str = include_str!("testdata/first.cert.pem");
+    const FIRST_KEY: &str = include_str!("testdata/first.key.pem");
+    const SECOND_CERTIFICATE: &str = include_str!("testdata/second.cert.pem");
+    const SECOND_KEY: &str = include_str!("testdata/second.key.pem");
+
+    #[tokio::test]
+    async fn reloads_changed_certificates() {
+        let directory = std::env::temp_dir();
+        let id = std::process::id();
+        let certificate = directory.join(format!("tbot-{}.cert.pem", id));
+        let private_key = directory.join(format!("tbot-{}.key.pem", id));
+        let files = CertificateFiles::new(&certificate, &private_key);
+
+        fs::write(&certificate, FIRST_CERTIFICATE).await.unwrap();
+        fs::write(&private_key, FIRST_KEY).await.unwrap();
+        let mut last_modified = files.modified().await.ok();
+        assert!(files.reload(&mut last_modified).await.is_none());
+
+        // files may change within the file system's timestamp granularity,
+        // so a change is simulated with an outdated modification time
+        let outdated = Some((UNIX_EPOCH, UNIX_EPOCH));
+        fs::write(&certificate, SECOND_CERTIFICATE).await.unwrap();
+        fs::write(&private_key, SECOND_KEY).await.unwrap();
+        let mut last_modified = outdated;
+        let (_, reloaded) = files.reload(&mut last_modified).await.unwrap();
+        assert_eq!(reloaded, SECOND_CERTIFICATE);
+        assert_eq!(last_modified, files.modified().await.ok());
+        assert!(files.reload(&mut last_modified).await.is_none());
+
+        // a broken key is retried on the next check rather than skipped
+        fs::write(&private_key, "not a key").await.unwrap();
+        let mut last_modified = outdated;
+        assert!(files.reload(&mut last_modified).await.is_none());
+        assert_eq!(last_modified, outdated);
+
+        fs::write(&private_key, SECOND_KEY).await.unwrap();
+        assert!(files.reload(&mut last_modified).await.is_some());
+
+        fs::remove_file(&certificate).await.unwrap();
+        fs::remove_file(&private_key).await.unwrap();
+    }
+}
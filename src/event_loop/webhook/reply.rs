@@ -0,0 +1,83 @@
+use crate::{
+    bot::{InnerBot, InterceptedCall},
+    methods::WebhookReply,
+};
+use futures::channel::oneshot;
+use serde_json::Value;
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::{Arc, Mutex},
+};
+
+/// A slot for a method which is sent in the response to a webhook request.
+///
+/// The slot is attached to the `Bot` passed to the contexts of an update.
+/// Once every clone of the slot is dropped, the webhook server stops waiting
+/// for a reply.
+#[derive(Clone)]
+pub struct Slot(Arc<Mutex<Option<oneshot::Sender<Vec<u8>>>>>);
+
+impl Slot {
+    pub fn new() -> (Self, oneshot::Receiver<Vec<u8>>) {
+        let (sender, receiver) = oneshot::channel();
+        (Self(Arc::new(Mutex::new(Some(sender)))), receiver)
+    }
+
+    /// Puts `method` in the slot unless the slot is already taken or one
+    /// of the bot's interceptors vetoes the method.
+    pub fn put<M: WebhookReply>(
+        &self,
+        bot: &InnerBot,
+        method: M,
+    ) -> Result<(), M> {
+        let mut body = match serde_json::to_value(&method) {
+            Ok(Value::Object(body)) => body,
+            _ => return Err(method),
+        };
+
+        let interceptors = bot.interceptors();
+        if !interceptors.is_empty() {
+            let mut call = InterceptedCall {
+                method: M::NAME,
+                boundary: None,
+                // serializing a JSON object back can't fail
+                body: serde_json::to_vec(&body).unwrap().into(),
+                is_rewritten: false,
+            };
+
+            let is_vetoed = interceptors
+                .iter()
+                .any(|interceptor| interceptor.before_call(&mut call).is_err());
+            if is_vetoed {
+                return Err(method);
+            }
+
+            if call.is_rewritten {
+                let rewritten = call.body().to_vec();
+                body = match serde_json::from_slice(&rewritten) {
+                    Ok(Value::Object(body)) => body,
+                    _ => return Err(method),
+                };
+            }
+        }
+
+        let sender = self.0.lock().unwrap().take();
+        let sender = match sender {
+            Some(sender) if !sender.is_canceled() => sender,
+            _ => return Err(method),
+        };
+
+        body.insert(String::from("method"), Value::from(M::NAME));
+
+        // serializing a JSON object back can't fail
+        let body = serde_json::to_vec(&body).unwrap();
+
+        sender.send(body).map_err(|_| method)
+    }
+}
+
+impl Debug for Slot {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("Slot(..)")
+    }
+}
@@ -0,0 +1,558 @@
+use super::reply;
+use crate::{event_loop::EventLoop, types::Update};
+use futures::future::BoxFuture;
+use hyper::{
+    body::{Body, HttpBody},
+    header::{HeaderValue, ALLOW, CONTENT_LENGTH, CONTENT_TYPE},
+    Method, Request, Response, StatusCode,
+};
+use std::{
+    num::NonZeroUsize,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::timeout;
+use tracing::warn;
+
+/// The default maximum size of a request's body.
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// A [`hyper`] service that handles updates sent by Telegram.
+///
+/// `Service` lets you mount your bot on a server you already run: you can
+/// route requests to several bots on one port or add your own routes next to
+/// the bot's one. Since `hyper`'s services are [`tower`] services, you can use
+/// it with any framework built on top of `tower` as well.
+///
+/// Note that `Service` only handles updates and does not call `setWebhook`.
+/// If you want `tbot` to do it, construct a `Service` with
+/// [`Webhook::register`]. [`webhook::Http`] and [`webhook::Https`] use
+/// `Service` under the hood.
+///
+/// [`tower`]: https://docs.rs/tower
+/// [`Webhook::register`]: super::Webhook::register
+/// [`webhook::Http`]: super::Http
+/// [`webhook::Https`]: super::Https
+#[derive(Clone)]
+#[must_use]
+pub struct Service {
+    pub(super) event_loop: Arc<EventLoop>,
+    updates_url: Arc<String>,
+    reply_timeout: Option<Duration>,
+    max_body_size: usize,
+    max_running_handlers: Option<NonZeroUsize>,
+    health_check_url: Option<Arc<String>>,
+    metrics_url: Option<Arc<String>>,
+}
+
+impl Service {
+    /// Constructs a `Service` handling updates with the provided event loop.
+    /// The service accepts updates on `/` unless you configure
+    /// [`accept_updates_on`].
+    ///
+    /// [`accept_updates_on`]: Self::accept_updates_on
+    pub fn new(event_loop: EventLoop) -> Self {
+        Self {
+            event_loop: Arc::new(event_loop),
+            updates_url: Arc::new(String::from("/")),
+            reply_timeout: None,
+            max_body_size: MAX_BODY_SIZE,
+            max_running_handlers: None,
+            health_check_url: None,
+            metrics_url: None,
+        }
+    }
+
+    /// Configures the URL that the service will accept updates on.
+    /// `/` by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URL doesn't starts with `/`.
+    pub fn accept_updates_on(mut self, url: String) -> Self {
+        if !url.starts_with('/') {
+            panic!(
+                "[tbot] `Service::accept_updates_on` takes URLs starting only \
+                 with `/`"
+            );
+        }
+
+        self.updates_url = Arc::new(url);
+        self
+    }
+
+    /// Allows handlers to send a method in the response to the webhook
+    /// request using [`Bot::reply_via_webhook`]. The response is held until
+    /// a handler sends a method, all handlers drop their contexts, or
+    /// `timeout` passes.
+    ///
+    /// Keep `timeout` short: the Bot API server does not send the next update
+    /// until it gets the response.
+    ///
+    /// [`Bot::reply_via_webhook`]: crate::Bot::reply_via_webhook
+    pub const fn allow_replies(mut self, timeout: Duration) -> Self {
+        self.reply_timeout = Some(timeout);
+        self
+    }
+
+    /// Configures the maximum size of a request's body. If a request is larger,
+    /// the service responds with `413 Payload Too Large`. 1 megabyte by default.
+    pub const fn max_body_size(mut self, size: usize) -> Self {
+        self.max_body_size = size;
+        self
+    }
+
+    /// Configures how many handlers may run at the same time. While this
+    /// limit is reached, the service responds with `503 Service Unavailable`
+    /// and the Bot API server will deliver the update later. Not limited
+    /// by default.
+    pub const fn max_running_handlers(mut self, limit: NonZeroUsize) -> Self {
+        self.max_running_handlers = Some(limit);
+        self
+    }
+
+    /// Enables a health check route on the provided URL, usually `/healthz`.
+    ///
+    /// The route responds to `GET` requests with `200 OK`, or with
+    /// `503 Service Unavailable` while the [`max_running_handlers`] limit
+    /// is reached, so it can be used both as a liveness and a readiness probe.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URL doesn't starts with `/`.
+    ///
+    /// [`max_running_handlers`]: Self::max_running_handlers
+    pub fn health_check_on(mut self, url: String) -> Self {
+        if !url.starts_with('/') {
+            panic!(
+                "[tbot] `Service::health_check_on` takes URLs starting only \
+                 with `/`"
+            );
+        }
+
+        self.health_check_url = Some(Arc::new(url));
+        self
+    }
+
+    /// Enables a metrics route on the provided URL, usually `/metrics`.
+    ///
+    /// The route responds to `GET` requests with metrics in the Prometheus
+    /// text format: received updates per kind, updates that failed to parse,
+    /// currently running handlers, and the latency and errors of calls
+    /// to each Bot API method. A call is counted once even if it's retried,
+    /// and its latency includes all attempts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URL doesn't starts with `/`.
+    pub fn metrics_on(mut self, url: String) -> Self {
+        if !url.starts_with('/') {
+            panic!(
+                "[tbot] `Service::metrics_on` takes URLs starting only with `/`"
+            );
+        }
+
+        self.metrics_url = Some(Arc::new(url));
+        self
+    }
+
+    fn is_overloaded(&self) -> bool {
+        self.max_running_handlers.map_or(false, |limit| {
+            self.event_loop.in_flight.count() >= limit.get()
+        })
+    }
+}
+
+impl hyper::service::Service<Request<Body>> for Service {
+    type Response = Response<Body>;
+    type Error = hyper::Error;
+    type Future = BoxFuture<'static, Result<Response<Body>, hyper::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        _: &mut Context<'_>,
+    ) -> Poll<Result<(), hyper::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        Box::pin(handle(self.clone(), request))
+    }
+}
+
+fn with_status(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+/// Reads the body of a request. Returns `None` if the body is larger than
+/// `limit`.
+async fn read_body(
+    request: Request<Body>,
+    limit: usize,
+) -> Result<Option<Vec<u8>>, hyper::Error> {
+    let (parts, mut body) = request.into_parts();
+    let content_length: Option<usize> = parts
+        .headers
+        .get(CONTENT_LENGTH)
+        .and_then(|x| x.to_str().ok().and_then(|x| x.parse().ok()));
+
+    if content_length.map_or(false, |length| length > limit) {
+        return Ok(None);
+    }
+
+    let mut request = content_length.map_or_else(Vec::new, Vec::with_capacity);
+
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+
+        if request.len() + chunk.len() > limit {
+            return Ok(None);
+        }
+
+        request.extend(chunk);
+    }
+
+    Ok(Some(request))
+}
+
+fn is_on(request: &Request<Body>, url: Option<&Arc<String>>) -> bool {
+    url.map_or(false, |url| request.uri() == url.as_str())
+}
+
+fn method_not_allowed(allow: &'static str) -> Response<Body> {
+    let mut response = with_status(StatusCode::METHOD_NOT_ALLOWED);
+    response
+        .headers_mut()
+        .insert(ALLOW, HeaderValue::from_static(allow));
+    response
+}
+
+fn handle_health_check(
+    service: &Service,
+    request: &Request<Body>,
+) -> Response<Body> {
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        return method_not_allowed("GET, HEAD");
+    }
+
+    if service.is_overloaded() {
+        return with_status(StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    Response::new(Body::from("OK"))
+}
+
+fn handle_metrics(
+    service: &Service,
+    request: &Request<Body>,
+) -> Response<Body> {
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        return method_not_allowed("GET, HEAD");
+    }
+
+    let event_loop = &service.event_loop;
+    let metrics = event_loop
+        .bot
+        .metrics()
+        .render(event_loop.in_flight.count());
+
+    let mut response = Response::new(Body::from(metrics));
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+    response
+}
+
+async fn handle(
+    service: Service,
+    request: Request<Body>,
+) -> Result<Response<Body>, hyper::Error> {
+    if is_on(&request, service.health_check_url.as_ref()) {
+        return Ok(handle_health_check(&service, &request));
+    }
+
+    if is_on(&request, service.metrics_url.as_ref()) {
+        return Ok(handle_metrics(&service, &request));
+    }
+
+    if request.uri() != service.updates_url.as_str() {
+        return Ok(with_status(StatusCode::NOT_FOUND));
+    }
+
+    if request.method() != Method::POST {
+        return Ok(method_not_allowed("POST"));
+    }
+
+    let is_json = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .map_or(false, |x| x == "application/json");
+    if !is_json {
+        return Ok(with_status(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+    }
+
+    if service.is_overloaded() {
+        warn!(
+            "Too many handlers are running, asking to deliver the update later"
+        );
+        return Ok(with_status(StatusCode::SERVICE_UNAVAILABLE));
+    }
+
+    let request = match read_body(request, service.max_body_size).await? {
+        Some(request) => request,
+        None => return Ok(with_status(StatusCode::PAYLOAD_TOO_LARGE)),
+    };
+
+    let update = match serde_json::from_slice(&request[..]) {
+        Ok(update) => update,
+        Err(error) if error.is_data() => {
+            service.event_loop.bot.metrics().record_parse_failure();
+            eprintln!(
+                "[tbot] Failed to parse an update: {:?}. `tbot` will skip it, \
+                 but this error means that `tbot`'s type deserialization \
+                 doesn't match the Bot API. You should file an issue at \
+                 https://gitlab.com/SnejUgal/tbot.",
+                error
+            );
+            // Telegram would keep redelivering an update `tbot` can never
+            // parse, so it's acknowledged instead.
+            return Ok(with_status(StatusCode::OK));
+        }
+        Err(error) => {
+            service.event_loop.bot.metrics().record_parse_failure();
+            warn!(%error, "Received a request with malformed JSON");
+            return Ok(with_status(StatusCode::BAD_REQUEST));
+        }
+    };
+
+    let Service {
+        event_loop,
+        reply_timeout,
+        ..
+    } = service;
+
+    if let Some(reply_timeout) = reply_timeout {
+        return Ok(handle_with_reply(&event_loop, update, reply_timeout).await);
+    }
+
+    event_loop.handle_update(update);
+    Ok(Response::new(Body::empty()))
+}
+
+async fn handle_with_reply(
+    event_loop: &EventLoop,
+    update: Update,
+    reply_timeout: Duration,
+) -> Response<Body> {
+    let (slot, reply) = reply::Slot::new();
+    let bot = event_loop.bot.with_webhook_reply(slot);
+    event_loop.handle_update_with_bot(&bot, update);
+    drop(bot);
+
+    if let Ok(Ok(reply)) = timeout(reply_timeout, reply).await {
+        let mut response = Response::new(Body::from(reply));
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        return response;
+    }
+
+    // the timeout passed, or every handler dropped its context
+    Response::new(Body::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bot, prelude::*};
+    use hyper::service::Service as _;
+    use std::convert::Infallible;
+
+    const TEXT_UPDATE: &str = r#"{
+        "update_id": 1,
+        "message": {
+            "message_id": 1,
+            "date": 0,
+            "chat": { "id": 42, "type": "private", "first_name": "Alice" },
+            "from": { "id": 42, "is_bot": false, "first_name": "Alice" },
+            "text": "hello"
+        }
+    }"#;
+
+    fn event_loop() -> EventLoop {
+        bot::Builder::with_string_token(String::from("TOKEN"))
+            .build()
+            .event_loop()
+    }
+
+    fn update_request(body: &'static str) -> Request<Body> {
+        Request::post("/")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    async fn respond(
+        service: &mut Service,
+        request: Request<Body>,
+    ) -> Response<Body> {
+        // none of the responses should wait for the reply timeout to pass
+        timeout(Duration::from_secs(5), service.call(request))
+            .await
+            .expect("[tbot] the service didn't respond in time")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn responds_with_replies() {
+        let mut event_loop = event_loop();
+        event_loop.text(|context| async move {
+            let reply = context.send_message("hi");
+            assert!(context.bot.reply_via_webhook(reply).is_ok());
+        });
+        let mut service =
+            Service::new(event_loop).allow_replies(Duration::from_secs(60));
+
+        let response = respond(&mut service, update_request(TEXT_UPDATE)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["method"], "sendMessage");
+        assert_eq!(body["chat_id"], 42);
+        assert_eq!(body["text"], "hi");
+    }
+
+    #[tokio::test]
+    async fn stops_waiting_for_replies_after_timeout() {
+        let mut event_loop = event_loop();
+        event_loop.text(|context| async move {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            drop(context);
+        });
+        let mut service =
+            Service::new(event_loop).allow_replies(Duration::from_millis(10));
+
+        let response = respond(&mut service, update_request(TEXT_UPDATE)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stops_waiting_for_replies_once_contexts_are_dropped() {
+        let mut event_loop = event_loop();
+        event_loop.text(|_| async {});
+        let mut service =
+            Service::new(event_loop).allow_replies(Duration::from_secs(60));
+
+        let response = respond(&mut service, update_request(TEXT_UPDATE)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(body.is_empty());
+    }
+
+    fn request(method: Method, uri: &str, body: &'static str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_requests() {
+        let mut service = Service::new(event_loop())
+            .accept_updates_on(String::from("/updates"))
+            .max_body_size(TEXT_UPDATE.len() - 1);
+
+        let wrong_path = request(Method::POST, "/", TEXT_UPDATE);
+        let response = respond(&mut service, wrong_path).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let wrong_method = request(Method::GET, "/updates", "");
+        let response = respond(&mut service, wrong_method).await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[ALLOW], "POST");
+
+        let mut not_json = request(Method::POST, "/updates", TEXT_UPDATE);
+        not_json
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+        let response = respond(&mut service, not_json).await;
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        // the body is small, but the declared length is checked first
+        let mut declared_too_large = request(Method::POST, "/updates", "{}");
+        declared_too_large
+            .headers_mut()
+            .insert(CONTENT_LENGTH, HeaderValue::from(TEXT_UPDATE.len()));
+        let response = respond(&mut service, declared_too_large).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let too_large = request(Method::POST, "/updates", TEXT_UPDATE);
+        let response = respond(&mut service, too_large).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let malformed = request(Method::POST, "/updates", "{");
+        let response = respond(&mut service, malformed).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Telegram would redeliver an update which `tbot` can't parse forever
+        let unparseable =
+            request(Method::POST, "/updates", r#"{"message":{}}"#);
+        let response = respond(&mut service, unparseable).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn reads_bodies_up_to_limit() {
+        let streamed = || {
+            let chunks = vec!["12", "345"].into_iter().map(Ok::<_, Infallible>);
+            Request::new(Body::wrap_stream(futures::stream::iter(chunks)))
+        };
+        let body = read_body(streamed(), 5).await.unwrap();
+        assert_eq!(body.as_deref(), Some(&b"12345"[..]));
+        assert_eq!(read_body(streamed(), 4).await.unwrap(), None);
+
+        let mut declared = Request::new(Body::from("12345"));
+        declared
+            .headers_mut()
+            .insert(CONTENT_LENGTH, HeaderValue::from(6));
+        assert_eq!(read_body(declared, 5).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn is_unavailable_while_overloaded() {
+        let limit = NonZeroUsize::new(2).unwrap();
+        let mut service = Service::new(event_loop())
+            .max_running_handlers(limit)
+            .health_check_on(String::from("/healthz"));
+        let in_flight = service.event_loop.in_flight.clone();
+
+        let first = in_flight.enter();
+        let response =
+            respond(&mut service, request(Method::GET, "/healthz", "")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let second = in_flight.enter();
+        let response =
+            respond(&mut service, request(Method::GET, "/healthz", "")).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let response = respond(&mut service, update_request(TEXT_UPDATE)).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        drop((first, second));
+        let response =
+            respond(&mut service, request(Method::GET, "/healthz", "")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = respond(&mut service, update_request(TEXT_UPDATE)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
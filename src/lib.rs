@@ -80,7 +80,7 @@ mod multipart;
 mod token;
 
 pub mod compositors;
-mod connectors;
+pub mod connectors;
 pub mod contexts;
 pub mod errors;
 pub mod event_loop;
@@ -92,7 +92,7 @@ pub mod state;
 pub mod types;
 pub mod util;
 
-use {download_file::download_file, multipart::Multipart};
+use multipart::{Multipart, RequestBody};
 
 pub use {bot::Bot, event_loop::EventLoop};
 
@@ -87,6 +87,7 @@ mod leave_chat;
 mod log_out;
 mod pin_chat_message;
 mod promote_chat_member;
+mod raw_method;
 mod restrict_chat_member;
 mod revoke_chat_invite_link;
 mod send_animation;
@@ -95,6 +96,7 @@ mod send_chat_action;
 mod send_contact;
 mod send_dice;
 mod send_document;
+mod send_file;
 mod send_game;
 mod send_invoice;
 mod send_location;
@@ -127,6 +129,7 @@ mod unban_chat_member;
 mod unpin_all_chat_messages;
 mod unpin_chat_message;
 mod upload_sticker_file;
+mod webhook_reply;
 
 pub use add_sticker_to_set::AddStickerToSet;
 pub use answer_callback_query::AnswerCallbackQuery;
@@ -134,6 +137,7 @@ pub use answer_inline_query::AnswerInlineQuery;
 pub use answer_pre_checkout_query::AnswerPreCheckoutQuery;
 pub use answer_shipping_query::AnswerShippingQuery;
 pub use ban_chat_member::BanChatMember;
+pub use close::Close;
 pub use copy_message::CopyMessage;
 pub use create_chat_invite_link::CreateChatInviteLink;
 pub use create_new_sticker_set::CreateNewStickerSet;
@@ -167,8 +171,10 @@ pub use get_sticker_set::GetStickerSet;
 pub use get_user_profile_photos::GetUserProfilePhotos;
 pub use get_webhook_info::GetWebhookInfo;
 pub use leave_chat::LeaveChat;
+pub use log_out::LogOut;
 pub use pin_chat_message::PinChatMessage;
 pub use promote_chat_member::PromoteChatMember;
+pub use raw_method::RawMethod;
 pub use restrict_chat_member::RestrictChatMember;
 pub use revoke_chat_invite_link::RevokeChatInviteLink;
 pub use send_animation::SendAnimation;
@@ -177,6 +183,7 @@ pub use send_chat_action::SendChatAction;
 pub use send_contact::SendContact;
 pub use send_dice::SendDice;
 pub use send_document::SendDocument;
+pub use send_file::SendFile;
 pub use send_game::SendGame;
 pub use send_invoice::SendInvoice;
 pub use send_location::SendLocation;
@@ -208,11 +215,12 @@ pub use unban_chat_member::UnbanChatMember;
 pub use unpin_all_chat_messages::UnpinAllChatMessages;
 pub use unpin_chat_message::UnpinChatMessage;
 pub use upload_sticker_file::UploadStickerFile;
+pub use webhook_reply::WebhookReply;
 
 pub(crate) use {
-    close::Close, delete_webhook::DeleteWebhook, get_updates::GetUpdates,
-    log_out::LogOut, set_webhook::SetWebhook,
+    delete_webhook::DeleteWebhook, get_updates::GetUpdates,
+    set_webhook::SetWebhook,
 };
 
 mod call_method;
-use call_method::call_method;
+use call_method::{call_method, CallOptions};
@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -10,6 +10,7 @@ use crate::{
     },
     Multipart,
 };
+use std::time::Duration;
 
 /// Adds a new sticker to an existing sticker set.
 ///
@@ -20,6 +21,7 @@ use crate::{
 #[must_use = "methods do nothing unless turned into a future"]
 pub struct AddStickerToSet<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
     user_id: user::Id,
     name: String,
     sticker: StickerForStickerSet,
@@ -37,6 +39,7 @@ impl<'a> AddStickerToSet<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             user_id,
             name: name.into(),
             sticker: sticker.into(),
@@ -53,6 +56,23 @@ impl<'a> AddStickerToSet<'a> {
 }
 
 impl AddStickerToSet<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         let mut multipart = Multipart::new(5)
@@ -72,17 +92,25 @@ impl AddStickerToSet<'_> {
 
         match &media {
             InputFile::File {
-                filename, bytes, ..
-            } => multipart = multipart.file(field, filename, bytes),
-            InputFile::Id(file::Id(sticker)) | InputFile::Url(sticker) => {
+                filename, source, ..
+            } => multipart = multipart.input_file(field, filename, source),
+            InputFile::Id(file::Id(sticker))
+            | InputFile::Url(sticker)
+            | InputFile::Local(sticker) => {
                 multipart = multipart.str(field, sticker);
             }
         }
 
         let (boundary, body) = multipart.finish();
 
-        call_method::<bool>(self.bot, "addStickerToSet", Some(boundary), body)
-            .await?;
+        call_method::<bool>(
+            self.bot,
+            self.options.local_file(&media),
+            "addStickerToSet",
+            Some(boundary),
+            body,
+        )
+        .await?;
 
         Ok(())
     }
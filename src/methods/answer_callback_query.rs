@@ -1,10 +1,11 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
     types::{callback, parameters::CallbackAction},
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Answers a callback query.
 ///
@@ -16,6 +17,8 @@ use serde::Serialize;
 pub struct AnswerCallbackQuery<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     callback_query_id: callback::query::Id,
     #[serde(skip_serializing_if = "Option::is_none")]
     text: Option<String>,
@@ -37,6 +40,7 @@ impl<'a> AnswerCallbackQuery<'a> {
         match action {
             None => Self {
                 bot,
+                options: CallOptions::new(),
                 callback_query_id,
                 text: None,
                 show_alert: None,
@@ -45,6 +49,7 @@ impl<'a> AnswerCallbackQuery<'a> {
             },
             Some(CallbackAction::Url(url)) => Self {
                 bot,
+                options: CallOptions::new(),
                 callback_query_id,
                 text: None,
                 show_alert: None,
@@ -53,6 +58,7 @@ impl<'a> AnswerCallbackQuery<'a> {
             },
             Some(CallbackAction::Text(text, show_alert)) => Self {
                 bot,
+                options: CallOptions::new(),
                 callback_query_id,
                 text: Some(text),
                 show_alert: Some(show_alert),
@@ -71,10 +77,28 @@ impl<'a> AnswerCallbackQuery<'a> {
 }
 
 impl AnswerCallbackQuery<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "answerCallbackQuery",
             None,
             serde_json::to_vec(&self).unwrap(),
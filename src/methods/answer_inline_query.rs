@@ -1,6 +1,7 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{bot::InnerBot, errors, types::inline_query};
 use serde::Serialize;
+use std::time::Duration;
 
 /// Answers an inline query.
 ///
@@ -12,6 +13,8 @@ use serde::Serialize;
 pub struct AnswerInlineQuery<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     inline_query_id: inline_query::Id,
     results: Vec<inline_query::Result>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -34,6 +37,7 @@ impl<'a> AnswerInlineQuery<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             inline_query_id,
             results: results.into(),
             cache_time: None,
@@ -80,10 +84,28 @@ impl<'a> AnswerInlineQuery<'a> {
 }
 
 impl AnswerInlineQuery<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "answerInlineQuery",
             None,
             serde_json::to_vec(&self).unwrap(),
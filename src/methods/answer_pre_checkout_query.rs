@@ -1,6 +1,7 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{bot::InnerBot, errors, types::pre_checkout_query};
 use serde::Serialize;
+use std::time::Duration;
 
 /// Answers a pre-checkout query.
 ///
@@ -12,6 +13,8 @@ use serde::Serialize;
 pub struct AnswerPreCheckoutQuery<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     pre_checkout_query_id: pre_checkout_query::Id,
     ok: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -26,6 +29,7 @@ impl<'a> AnswerPreCheckoutQuery<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             pre_checkout_query_id,
             ok: result.is_ok(),
             error_message: result.err().map(Into::into),
@@ -34,10 +38,28 @@ impl<'a> AnswerPreCheckoutQuery<'a> {
 }
 
 impl AnswerPreCheckoutQuery<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "answerPreCheckoutQuery",
             None,
             serde_json::to_vec(&self).unwrap(),
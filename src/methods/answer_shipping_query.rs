@@ -1,6 +1,7 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{bot::InnerBot, errors, types::shipping};
 use serde::Serialize;
+use std::time::Duration;
 
 /// Answers a shipping query.
 ///
@@ -12,6 +13,8 @@ use serde::Serialize;
 pub struct AnswerShippingQuery<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     shipping_query_id: shipping::query::Id,
     ok: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,6 +32,7 @@ impl<'a> AnswerShippingQuery<'a> {
         if result.is_ok() {
             Self {
                 bot,
+                options: CallOptions::new(),
                 shipping_query_id,
                 ok: true,
                 shipping_options: result.ok().map(Into::into),
@@ -37,6 +41,7 @@ impl<'a> AnswerShippingQuery<'a> {
         } else {
             Self {
                 bot,
+                options: CallOptions::new(),
                 shipping_query_id,
                 ok: false,
                 shipping_options: None,
@@ -47,10 +52,28 @@ impl<'a> AnswerShippingQuery<'a> {
 }
 
 impl AnswerShippingQuery<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "answerShippingQuery",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -8,6 +8,7 @@ use crate::{
     },
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Bans a member in a chat.
 ///
@@ -19,6 +20,8 @@ use serde::Serialize;
 pub struct BanChatMember<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     chat_id: ChatId,
     user_id: user::Id,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -34,6 +37,7 @@ impl<'a> BanChatMember<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             user_id,
             until_date: None,
@@ -57,10 +61,28 @@ impl<'a> BanChatMember<'a> {
 }
 
 impl BanChatMember<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "banChatMember",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -1,17 +1,64 @@
-use crate::{bot::InnerBot, errors, types::chat};
+use crate::{
+    bot::{InnerBot, InterceptedCall, RateLimitedChat},
+    errors,
+    multipart::{find, find_field},
+    types::{chat, input_file::InputFile, parameters::ChatId},
+    RequestBody,
+};
+use futures::{
+    future::{select, Either},
+    pin_mut,
+};
 use hyper::{
-    body::{Body, HttpBody},
-    header::HeaderValue,
-    http::uri::PathAndQuery,
-    Method, Request, Uri,
+    body::HttpBody, header::HeaderValue, http::uri::PathAndQuery, Body, Method,
+    Request, Uri,
 };
 use serde::{de::DeserializeOwned, Deserialize};
 use std::{
     error::Error,
     fmt::{self, Debug, Formatter, Write},
     str::from_utf8,
+    time::{Duration, Instant},
 };
-use tracing::{error, instrument, trace};
+use tokio::{fs, time::sleep};
+use tracing::{error, info, instrument, trace, warn};
+
+/// Options for a single method call which aren't sent to the Bot API.
+#[derive(Debug, Clone, Copy)]
+pub struct CallOptions {
+    pub disable_retries: bool,
+    /// The chat a message is sent to, if the method is subject to the bot's
+    /// rate limits.
+    pub rate_limited_chat: Option<RateLimitedChat>,
+    /// For how long to wait for the response once the body has been sent.
+    pub timeout: Option<Duration>,
+    /// Whether the call refers to files on the machine of a self-hosted
+    /// Bot API server.
+    pub local_files: bool,
+}
+
+impl CallOptions {
+    pub const fn new() -> Self {
+        Self {
+            disable_retries: false,
+            rate_limited_chat: None,
+            timeout: None,
+            local_files: false,
+        }
+    }
+
+    pub fn rate_limited(mut self, chat_id: &ChatId) -> Self {
+        self.rate_limited_chat = Some(chat_id.into());
+        self
+    }
+
+    pub const fn local_file(mut self, file: &InputFile) -> Self {
+        if file.is_local() {
+            self.local_files = true;
+        }
+        self
+    }
+}
 
 struct DebugBytes<'a>(&'a [u8]);
 
@@ -39,10 +86,7 @@ struct Response<T> {
     parameters: Option<ResponseParameters>,
 }
 
-fn construct_uri(
-    bot: &InnerBot,
-    method: &'static str,
-) -> Result<Uri, Box<dyn Error>> {
+fn construct_uri(bot: &InnerBot, method: &str) -> Result<Uri, Box<dyn Error>> {
     let mut uri_parts = bot.uri().into_parts();
     let path = uri_parts.path_and_query.as_ref().map_or("/", |x| x.path());
     let query = uri_parts
@@ -66,38 +110,241 @@ fn construct_uri(
     Uri::from_parts(uri_parts).map_err(Into::into)
 }
 
-#[instrument(skip(bot, boundary, body))]
+/// Replaces `chat_id` in a request's body with the chat's new ID. Returns
+/// the chat's old ID and the new body, or `None` if the body doesn't contain
+/// a numeric `chat_id`.
+///
+/// The Bot API doesn't say which chat migrated, so if the body also contains
+/// a `from_chat_id` (as with `copyMessage` and `forwardMessage`), the call
+/// may have failed because of that chat rather than `chat_id`. In this case,
+/// the migration is only followed if both IDs refer to the same chat.
+fn migrate_chat_id(
+    boundary: Option<&str>,
+    body: &[u8],
+    to: chat::Id,
+) -> Option<(chat::Id, Vec<u8>)> {
+    boundary.map_or_else(
+        || migrate_json_chat_id(body, to),
+        |boundary| migrate_multipart_chat_id(boundary, body, to),
+    )
+}
+
+fn migrate_json_chat_id(
+    body: &[u8],
+    to: chat::Id,
+) -> Option<(chat::Id, Vec<u8>)> {
+    let mut body: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_slice(body).ok()?;
+    let from = chat::Id(body.get("chat_id")?.as_i64()?);
+    for key in &["chat_id", "from_chat_id"] {
+        match body.get_mut(*key) {
+            Some(chat_id) if chat_id.as_i64() == Some(from.0) => {
+                *chat_id = to.0.into();
+            }
+            Some(_) => return None,
+            None => (),
+        }
+    }
+
+    Some((from, serde_json::to_vec(&body).unwrap()))
+}
+
+fn migrate_multipart_chat_id(
+    boundary: &str,
+    body: &[u8],
+    to: chat::Id,
+) -> Option<(chat::Id, Vec<u8>)> {
+    let from_chat_header = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"from_chat_id\"",
+        boundary
+    );
+    if find(body, from_chat_header.as_bytes()).is_some() {
+        return None;
+    }
+
+    let value = find_field(boundary, body, "chat_id")?;
+    let from = from_utf8(&body[value.clone()]).ok()?.parse().ok()?;
+
+    let mut migrated = Vec::with_capacity(body.len());
+    migrated.extend_from_slice(&body[..value.start]);
+    migrated.extend_from_slice(to.0.to_string().as_bytes());
+    migrated.extend_from_slice(&body[value.end..]);
+
+    Some((chat::Id(from), migrated))
+}
+
+/// If the call failed because the chat migrated to a supergroup and the bot
+/// follows migrations, calls the migration handler and returns the body
+/// to re-issue the call with.
+async fn follow_migration(
+    bot: &InnerBot,
+    boundary: Option<&str>,
+    body: &RequestBody,
+    error: &errors::MethodCall,
+) -> Option<(chat::Id, RequestBody)> {
+    let to = if let errors::MethodCall::RequestError {
+        migrate_to_chat_id,
+        ..
+    } = error
+    {
+        (*migrate_to_chat_id)?
+    } else {
+        return None;
+    };
+    let handler = bot.migration_handler()?;
+    let (from, body) =
+        body.rewrite(|body| migrate_chat_id(boundary, body, to))?;
+
+    info!(?from, ?to, "The chat migrated, re-issuing the call");
+    handler.call(from, to).await;
+
+    Some((to, body))
+}
+
+#[instrument(skip(bot, options, boundary, body))]
 pub async fn call_method<'a, T>(
     bot: &'a InnerBot,
-    method: &'static str,
+    mut options: CallOptions,
+    method: &str,
     boundary: Option<String>,
-    body: Vec<u8>,
+    body: impl Into<RequestBody>,
 ) -> Result<T, errors::MethodCall>
 where
     T: DeserializeOwned + Debug,
 {
-    trace!(body = ?DebugBytes(&body), ?boundary);
+    let body = body.into();
+    if options.local_files {
+        if !bot.is_self_hosted() {
+            let error = errors::MethodCall::LocalFile;
+            error!(?error);
+            return Err(error);
+        }
 
-    let mut request = Request::new(Body::from(body));
-    *request.method_mut() = Method::POST;
-    *request.uri_mut() = construct_uri(bot, method)
-        .expect("[tbot] Method URI construction failed");
+        for path in body.local_paths() {
+            if fs::metadata(&path).await.is_err() {
+                let error = errors::MethodCall::LocalFile;
+                error!(?error, ?path, "The local file doesn't exist");
+                return Err(error);
+            }
+        }
+    }
 
-    let content_type = if let Some(boundary) = boundary {
-        let value = format!("multipart/form-data; boundary={}", boundary);
+    let interceptors = bot.interceptors();
+    let started_at = Instant::now();
+    let mut call = InterceptedCall {
+        method,
+        boundary: boundary.as_deref(),
+        body,
+        is_rewritten: false,
+    };
 
-        // disallowed characters shouldn't appear
-        HeaderValue::from_str(&value).unwrap()
-    } else {
-        HeaderValue::from_static("application/json")
+    let veto = interceptors
+        .iter()
+        .find_map(|interceptor| interceptor.before_call(&mut call).err());
+
+    // An interceptor may have redirected the message to another chat.
+    if call.is_rewritten && options.rate_limited_chat.is_some() {
+        if let Some(chat_id) = call.chat_id() {
+            options = options.rate_limited(&chat_id);
+        }
+    }
+    let body = call.body;
+    let is_vetoed = veto.is_some();
+
+    let result = match veto {
+        Some(reason) => {
+            let error = errors::MethodCall::Vetoed(reason);
+            error!(?error);
+            Err(error)
+        }
+        None => call_with_retries(bot, options, method, boundary, body).await,
     };
 
-    request
-        .headers_mut()
-        .insert(hyper::header::CONTENT_TYPE, content_type);
+    let duration = started_at.elapsed();
+    if !is_vetoed {
+        bot.metrics()
+            .record_method_call(method, duration, result.is_ok());
+    }
+    for interceptor in interceptors {
+        interceptor.after_call(method, result.as_ref().map(|_| ()), duration);
+    }
+
+    result
+}
 
+async fn call_with_retries<T>(
+    bot: &InnerBot,
+    mut options: CallOptions,
+    method: &str,
+    boundary: Option<String>,
+    mut body: RequestBody,
+) -> Result<T, errors::MethodCall>
+where
+    T: DeserializeOwned + Debug,
+{
+    // Retrying a call which streams a reader would fail because the reader
+    // has been read, hiding the original error.
+    let is_replayable = body.is_replayable();
+    let retry_policy = bot
+        .retry_policy()
+        .filter(|_| !options.disable_retries && is_replayable);
+    let mut attempt = 1;
+    let mut has_migrated = false;
+    let request_timeout = options.timeout.or_else(|| bot.request_timeout());
+
+    loop {
+        if let (Some(limiter), Some(chat)) =
+            (bot.rate_limiter(), options.rate_limited_chat)
+        {
+            limiter.wait(chat).await;
+        }
+
+        let result = send_request(
+            bot,
+            method,
+            boundary.as_deref(),
+            body.clone(),
+            request_timeout,
+        )
+        .await;
+        let error = match result {
+            Ok(result) => return Ok(result),
+            Err(error) => error,
+        };
+
+        if !has_migrated && is_replayable {
+            let migration =
+                follow_migration(bot, boundary.as_deref(), &body, &error);
+
+            if let Some((to, migrated_body)) = migration.await {
+                body = migrated_body;
+                has_migrated = true;
+                if options.rate_limited_chat.is_some() {
+                    let to = ChatId::Id(to);
+                    options.rate_limited_chat = Some((&to).into());
+                }
+                continue;
+            }
+        }
+
+        let delay = retry_policy.and_then(|x| x.retry_delay(attempt, &error));
+        let delay = match delay {
+            Some(delay) => delay,
+            None => return Err(error),
+        };
+
+        warn!(attempt, ?delay, %error, "Retrying the method call");
+        sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+async fn receive_response(
+    bot: &InnerBot,
+    request: Request<Body>,
+) -> Result<Vec<u8>, errors::MethodCall> {
     let (parts, mut body) = bot
-        .client()
+        .transport()
         .request(request)
         .await
         .map_err(|error| {
@@ -121,6 +368,73 @@ where
         })?);
     }
 
+    Ok(response)
+}
+
+/// Sends the request. The timeout applies to waiting for the response once
+/// the body has been sent, so that uploading a large file doesn't time out.
+async fn send_request<T>(
+    bot: &InnerBot,
+    method: &str,
+    boundary: Option<&str>,
+    body: RequestBody,
+    request_timeout: Option<Duration>,
+) -> Result<T, errors::MethodCall>
+where
+    T: DeserializeOwned + Debug,
+{
+    if let Some(body) = body.in_memory() {
+        trace!(body = ?DebugBytes(body), ?boundary);
+    } else {
+        trace!(?boundary, "Streaming the body");
+    }
+
+    let (body, length, uploaded) =
+        body.into_hyper().await.map_err(|error| {
+            let error = errors::MethodCall::Upload(error);
+            error!(?error);
+            error
+        })?;
+
+    let mut request = Request::new(body);
+    *request.method_mut() = Method::POST;
+    *request.uri_mut() = construct_uri(bot, method)
+        .expect("[tbot] Method URI construction failed");
+
+    let content_type = if let Some(boundary) = boundary {
+        let value = format!("multipart/form-data; boundary={}", boundary);
+
+        // disallowed characters shouldn't appear
+        HeaderValue::from_str(&value).unwrap()
+    } else {
+        HeaderValue::from_static("application/json")
+    };
+
+    let headers = request.headers_mut();
+    headers.insert(hyper::header::CONTENT_TYPE, content_type);
+    headers.insert(hyper::header::CONTENT_LENGTH, length.into());
+
+    let response = receive_response(bot, request);
+    let response = match request_timeout {
+        Some(request_timeout) => {
+            let deadline = async move {
+                let _ = uploaded.await;
+                sleep(request_timeout).await;
+            };
+            pin_mut!(response, deadline);
+
+            match select(response, deadline).await {
+                Either::Left((response, _)) => response?,
+                Either::Right(_) => {
+                    let error = errors::MethodCall::Timeout;
+                    error!(?error);
+                    return Err(error);
+                }
+            }
+        }
+        None => response.await?,
+    };
+
     trace!(raw_response = ?DebugBytes(&response));
 
     if response.starts_with(b"<") {
@@ -169,12 +483,23 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{connectors::Client, token::Token};
+    use crate::{
+        bot::Interceptor,
+        connectors::{Transport, TransportError},
+        token::Token,
+        types::input_file::FileSource,
+        Multipart,
+    };
+    use futures::future::BoxFuture;
+    use hyper::Body;
+    use std::{
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    };
 
     #[test]
     fn construts_uri_correctly() -> Result<(), Box<dyn Error>> {
-        let mut bot =
-            InnerBot::new(Token(String::from("TOKEN")), Client::https());
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
 
         let uri = construct_uri(&bot, "method")?;
         assert_eq!(
@@ -227,4 +552,344 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn migrates_chat_id() {
+        let to = chat::Id(-1_001_234);
+
+        let body = br#"{"chat_id":-1234,"text":"foo"}"#;
+        let (from, body) = migrate_chat_id(None, body, to).unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(from, chat::Id(-1234));
+        assert_eq!(body["chat_id"], -1_001_234);
+        assert_eq!(body["text"], "foo");
+
+        let body = b"--==\r\n\
+            Content-Disposition: form-data; name=\"chat_id\"\r\n\r\n\
+            -1234\r\n\
+            --==--\r\n";
+        let (from, body) = migrate_chat_id(Some("=="), body, to).unwrap();
+        assert_eq!(from, chat::Id(-1234));
+        assert_eq!(
+            body,
+            &b"--==\r\n\
+            Content-Disposition: form-data; name=\"chat_id\"\r\n\r\n\
+            -1001234\r\n\
+            --==--\r\n"[..]
+        );
+
+        let body = br#"{"chat_id":"@username"}"#;
+        assert!(migrate_chat_id(None, body, to).is_none());
+
+        let body = br#"{"chat_id":-1234,"from_chat_id":-1234}"#;
+        let (_, body) = migrate_chat_id(None, body, to).unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["chat_id"], -1_001_234);
+        assert_eq!(body["from_chat_id"], -1_001_234);
+    }
+
+    /// Responds to every call as if the chat migrated to a supergroup.
+    #[derive(Debug, Default)]
+    struct Migrated(Arc<Mutex<Vec<Vec<u8>>>>);
+
+    impl Transport for Migrated {
+        fn request(
+            &self,
+            request: Request<Body>,
+        ) -> BoxFuture<'static, Result<hyper::Response<Body>, TransportError>>
+        {
+            let requests = Arc::clone(&self.0);
+            Box::pin(async move {
+                let body = hyper::body::to_bytes(request.into_body()).await?;
+                requests.lock().unwrap().push(body.to_vec());
+                Ok(hyper::Response::new(Body::from(
+                    r#"{"ok":false,"error_code":400,
+                    "description":"Bad Request: group chat was upgraded to a supergroup chat",
+                    "parameters":{"migrate_to_chat_id":-1001234}}"#,
+                )))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn ignores_migrations_of_the_source_chat() {
+        let requests = Arc::default();
+        let migrations = Arc::new(Mutex::new(Vec::new()));
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Migrated(Arc::clone(&requests)));
+        let handler_migrations = Arc::clone(&migrations);
+        bot.set_migration_handler(move |from, to| {
+            handler_migrations.lock().unwrap().push((from, to));
+            Box::pin(async {})
+        });
+
+        let body = br#"{"chat_id":42,"from_chat_id":-1234,"message_id":1}"#;
+        let result: Result<bool, _> = call_method(
+            &bot,
+            CallOptions::new(),
+            "copyMessage",
+            None,
+            body.to_vec(),
+        )
+        .await;
+
+        assert!(result.unwrap_err().is_request_error());
+        assert!(migrations.lock().unwrap().is_empty());
+        assert_eq!(*requests.lock().unwrap(), [body.to_vec()]);
+    }
+
+    #[derive(Debug, Default)]
+    struct Recording(Arc<Mutex<Vec<Uri>>>);
+
+    impl Transport for Recording {
+        fn request(
+            &self,
+            request: Request<Body>,
+        ) -> BoxFuture<'static, Result<hyper::Response<Body>, TransportError>>
+        {
+            self.0.lock().unwrap().push(request.uri().clone());
+            let body = Body::from(r#"{"ok":true,"result":true}"#);
+            Box::pin(async move { Ok(hyper::Response::new(body)) })
+        }
+    }
+
+    #[tokio::test]
+    async fn goes_through_custom_transport() {
+        let requests = Arc::default();
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Recording(Arc::clone(&requests)));
+
+        let result: bool =
+            call_method(&bot, CallOptions::new(), "close", None, Vec::new())
+                .await
+                .unwrap();
+
+        assert!(result);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [Uri::from_static("https://api.telegram.org/botTOKEN/close")]
+        );
+    }
+
+    #[derive(Debug)]
+    struct Unreachable;
+
+    impl Transport for Unreachable {
+        fn request(
+            &self,
+            _: Request<Body>,
+        ) -> BoxFuture<'static, Result<hyper::Response<Body>, TransportError>>
+        {
+            Box::pin(async { Err("the server is unreachable".into()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_custom_transport_errors() {
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Unreachable);
+
+        let result: Result<bool, _> =
+            call_method(&bot, CallOptions::new(), "close", None, Vec::new())
+                .await;
+
+        match result.unwrap_err() {
+            errors::MethodCall::Transport(error) => {
+                assert_eq!(error.to_string(), "the server is unreachable");
+            }
+            error => panic!("[tbot] unexpected error: {:?}", error),
+        }
+    }
+
+    #[derive(Debug)]
+    struct VetoClose;
+
+    impl Interceptor for VetoClose {
+        fn before_call(
+            &self,
+            call: &mut InterceptedCall<'_>,
+        ) -> Result<(), String> {
+            if call.method() == "close" {
+                return Err(String::from("closing is not allowed"));
+            }
+
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn vetoed_calls_are_not_sent() {
+        let requests = Arc::default();
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Recording(Arc::clone(&requests)));
+        bot.add_interceptor(VetoClose);
+
+        let result: Result<bool, _> =
+            call_method(&bot, CallOptions::new(), "close", None, Vec::new())
+                .await;
+        assert!(result.unwrap_err().is_vetoed());
+
+        let result: bool =
+            call_method(&bot, CallOptions::new(), "logOut", None, Vec::new())
+                .await
+                .unwrap();
+        assert!(result);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[derive(Debug)]
+    struct VetoChat(chat::Id);
+
+    impl Interceptor for VetoChat {
+        fn before_call(
+            &self,
+            call: &mut InterceptedCall<'_>,
+        ) -> Result<(), String> {
+            if call.chat_id() == Some(ChatId::Id(self.0)) {
+                return Err(String::from("the chat is blacklisted"));
+            }
+
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn vetoes_streamed_uploads() {
+        let requests = Arc::default();
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Recording(Arc::clone(&requests)));
+        bot.add_interceptor(VetoChat(chat::Id(42)));
+
+        let source = FileSource::Path(PathBuf::from("document.pdf"));
+        let chat_id = ChatId::Id(chat::Id(42));
+        let (boundary, body) = Multipart::new(2)
+            .chat_id("chat_id", &chat_id)
+            .input_file("document", "document.pdf", &source)
+            .finish();
+        assert!(body.in_memory().is_none());
+
+        let result: Result<bool, _> = call_method(
+            &bot,
+            CallOptions::new(),
+            "sendDocument",
+            Some(boundary),
+            body,
+        )
+        .await;
+        assert!(result.unwrap_err().is_vetoed());
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    /// Reads the body slowly, then responds after `delay`.
+    #[derive(Debug)]
+    struct SlowServer {
+        delay: Duration,
+    }
+
+    impl Transport for SlowServer {
+        fn request(
+            &self,
+            request: Request<Body>,
+        ) -> BoxFuture<'static, Result<hyper::Response<Body>, TransportError>>
+        {
+            let delay = self.delay;
+            Box::pin(async move {
+                let mut body = request.into_body();
+                while let Some(chunk) = body.data().await {
+                    chunk?;
+                    sleep(Duration::from_millis(50)).await;
+                }
+                sleep(delay).await;
+
+                let body = Body::from(r#"{"ok":true,"result":true}"#);
+                Ok(hyper::Response::new(body))
+            })
+        }
+    }
+
+    async fn upload_slowly(
+        delay: Duration,
+    ) -> Result<bool, errors::MethodCall> {
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(SlowServer { delay });
+
+        let path = std::env::current_dir().unwrap().join("Cargo.toml");
+        let source = FileSource::Path(path);
+        let (boundary, body) = Multipart::new(2)
+            .str("chat_id", "42")
+            .input_file("document", "Cargo.toml", &source)
+            .finish();
+        assert!(body.in_memory().is_none());
+
+        let options = CallOptions {
+            timeout: Some(Duration::from_millis(100)),
+            ..CallOptions::new()
+        };
+        call_method(&bot, options, "sendDocument", Some(boundary), body).await
+    }
+
+    #[tokio::test]
+    async fn timeout_starts_after_uploads() {
+        assert!(upload_slowly(Duration::from_millis(0)).await.unwrap());
+        let result = upload_slowly(Duration::from_millis(500)).await;
+        assert!(result.unwrap_err().is_timeout());
+    }
+
+    /// Builds a call which sends a local file at `path` to a chat.
+    fn send_local_document(path: PathBuf) -> (CallOptions, Vec<u8>) {
+        let file = InputFile::with_local_path(path, "Document");
+        let options = CallOptions::new().local_file(&file);
+        let uri = match file {
+            InputFile::Local(uri) => uri,
+            _ => unreachable!(),
+        };
+        let body = serde_json::json!({ "chat_id": 42, "document": uri });
+
+        (options, serde_json::to_vec(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn local_files_require_a_self_hosted_server() {
+        let path = std::env::current_dir().unwrap().join("Cargo.toml");
+        let (options, body) = send_local_document(path);
+
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Recording::default());
+        bot.set_uri(Uri::from_static("http://localhost:8081"));
+
+        let result: bool =
+            call_method(&bot, options, "sendDocument", None, body)
+                .await
+                .unwrap();
+        assert!(result);
+    }
+
+    #[tokio::test]
+    async fn local_files_are_rejected_by_the_cloud_server() {
+        let path = std::env::current_dir().unwrap().join("Cargo.toml");
+        let (options, body) = send_local_document(path);
+
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Recording::default());
+
+        let result: Result<bool, _> =
+            call_method(&bot, options, "sendDocument", None, body).await;
+        assert!(result.unwrap_err().is_local_file());
+    }
+
+    #[tokio::test]
+    async fn local_files_must_exist() {
+        let path = std::env::current_dir().unwrap().join("missing file.txt");
+        let (options, body) = send_local_document(path);
+
+        let requests = Arc::default();
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Recording(Arc::clone(&requests)));
+        bot.set_uri(Uri::from_static("http://localhost:8081"));
+
+        let result: Result<bool, _> =
+            call_method(&bot, options, "sendDocument", None, body).await;
+        assert!(result.unwrap_err().is_local_file());
+        assert!(requests.lock().unwrap().is_empty());
+    }
 }
@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -8,6 +8,7 @@ use crate::{
     },
 };
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 // Reflects the [`MessageId`][docs] type.
 //
@@ -27,6 +28,8 @@ struct MessageId {
 pub struct CopyMessage<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     chat_id: ChatId,
     from_chat_id: ChatId,
     message_id: message::Id,
@@ -52,6 +55,7 @@ impl<'a> CopyMessage<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             from_chat_id: from_chat_id.into(),
             message_id,
@@ -106,10 +110,28 @@ impl<'a> CopyMessage<'a> {
 }
 
 impl CopyMessage<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<message::Id, errors::MethodCall> {
         let result = call_method::<MessageId>(
             self.bot,
+            self.options.rate_limited(&self.chat_id),
             "copyMessage",
             None,
             serde_json::to_vec(&self).unwrap(),
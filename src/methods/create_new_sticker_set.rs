@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -10,6 +10,7 @@ use crate::{
     },
     Multipart,
 };
+use std::time::Duration;
 
 /// Creates a new sticker set.
 ///
@@ -20,6 +21,7 @@ use crate::{
 #[must_use = "methods do nothing unless turned into a future"]
 pub struct CreateNewStickerSet<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
     user_id: user::Id,
     name: String,
     title: String,
@@ -40,6 +42,7 @@ impl<'a> CreateNewStickerSet<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             user_id,
             name: name.into(),
             title: title.into(),
@@ -66,6 +69,23 @@ impl<'a> CreateNewStickerSet<'a> {
 }
 
 impl CreateNewStickerSet<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         let mut multipart = Multipart::new(7)
@@ -87,9 +107,11 @@ impl CreateNewStickerSet<'_> {
 
         match &media {
             InputFile::File {
-                filename, bytes, ..
-            } => multipart = multipart.file(field, filename, bytes),
-            InputFile::Id(file::Id(sticker)) | InputFile::Url(sticker) => {
+                filename, source, ..
+            } => multipart = multipart.input_file(field, filename, source),
+            InputFile::Id(file::Id(sticker))
+            | InputFile::Url(sticker)
+            | InputFile::Local(sticker) => {
                 multipart = multipart.str(field, sticker);
             }
         }
@@ -98,6 +120,7 @@ impl CreateNewStickerSet<'_> {
 
         call_method::<bool>(
             self.bot,
+            self.options.local_file(&media),
             "createNewStickerSet",
             Some(boundary),
             body,
@@ -1,6 +1,7 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{bot::InnerBot, errors};
 use serde::Serialize;
+use std::time::Duration;
 
 /// Deletes a sticker from a sticker set.
 ///
@@ -12,6 +13,8 @@ use serde::Serialize;
 pub struct DeleteStickerFromSet<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     sticker: String,
 }
 
@@ -19,16 +22,35 @@ impl<'a> DeleteStickerFromSet<'a> {
     pub(crate) fn new(bot: &'a InnerBot, sticker: impl Into<String>) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             sticker: sticker.into(),
         }
     }
 }
 
 impl DeleteStickerFromSet<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "deleteStickerFromSet",
             None,
             serde_json::to_vec(&self).unwrap(),
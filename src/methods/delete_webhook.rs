@@ -1,23 +1,33 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{bot::InnerBot, errors};
 
 #[derive(Debug, Clone)]
 #[must_use]
 pub struct DeleteWebhook<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
 }
 
 impl<'a> DeleteWebhook<'a> {
     pub(crate) const fn new(bot: &'a InnerBot) -> Self {
-        Self { bot }
+        Self {
+            bot,
+            options: CallOptions::new(),
+        }
     }
 }
 
 impl DeleteWebhook<'_> {
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
-        call_method::<bool>(self.bot, "deleteWebhook", None, Vec::new())
-            .await?;
+        call_method::<bool>(
+            self.bot,
+            self.options,
+            "deleteWebhook",
+            None,
+            Vec::new(),
+        )
+        .await?;
 
         Ok(())
     }
@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -9,6 +9,7 @@ use crate::{
     },
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Edits the caption of a media message sent via the inline mode.
 ///
@@ -20,6 +21,8 @@ use serde::Serialize;
 pub struct EditInlineCaption<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     inline_message_id: InlineMessageId,
     caption: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -38,6 +41,7 @@ impl<'a> EditInlineCaption<'a> {
 
         Self {
             bot,
+            options: CallOptions::new(),
             inline_message_id,
             caption: caption.text,
             parse_mode: caption.parse_mode,
@@ -55,10 +59,28 @@ impl<'a> EditInlineCaption<'a> {
 }
 
 impl EditInlineCaption<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "editMessageCaption",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -1,10 +1,11 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
     types::{keyboard::inline, InlineMessageId},
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Edits a live location sent via the inline mode.
 ///
@@ -16,6 +17,8 @@ use serde::Serialize;
 pub struct EditInlineLocation<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     inline_message_id: InlineMessageId,
     latitude: f64,
     longitude: f64,
@@ -31,6 +34,7 @@ impl<'a> EditInlineLocation<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             inline_message_id,
             latitude,
             longitude,
@@ -48,10 +52,28 @@ impl<'a> EditInlineLocation<'a> {
 }
 
 impl EditInlineLocation<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "editMessageLiveLocation",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -12,6 +12,7 @@ use serde::Serialize;
 use std::{
     convert::TryInto,
     num::{NonZeroU16, NonZeroU32},
+    time::Duration,
 };
 
 /// Edits a live location sent by the bot itself.
@@ -24,6 +25,8 @@ use std::{
 pub struct EditMessageLocation<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     chat_id: ChatId,
     message_id: message::Id,
     latitude: f64,
@@ -47,6 +50,7 @@ impl<'a> EditMessageLocation<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             message_id,
             latitude,
@@ -124,10 +128,28 @@ impl<'a> EditMessageLocation<'a> {
 }
 
 impl EditMessageLocation<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<Message, errors::MethodCall> {
         call_method(
             self.bot,
+            self.options,
             "editMessageLiveLocation",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -12,6 +12,7 @@ use crate::{
     },
     Multipart,
 };
+use std::time::Duration;
 
 /// Edits the media of a message sent by the bot itself.
 ///
@@ -22,6 +23,7 @@ use crate::{
 #[must_use = "methods do nothing unless turned into a future"]
 pub struct EditMessageMedia<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
     chat_id: ChatId,
     message_id: message::Id,
     media: EditableMedia,
@@ -37,6 +39,7 @@ impl<'a> EditMessageMedia<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             message_id,
             media: media.into(),
@@ -54,6 +57,23 @@ impl<'a> EditMessageMedia<'a> {
 }
 
 impl EditMessageMedia<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<Message, errors::MethodCall> {
         let mut multipart = Multipart::new(5)
@@ -67,15 +87,22 @@ impl EditMessageMedia<'_> {
             | EditableMedia::Document(Document { media, .. })
             | EditableMedia::Photo(Photo { media, .. })
             | EditableMedia::Video(Video { media, .. }) => {
-                if let InputFile::File { filename, bytes } = media {
-                    multipart =
-                        multipart.file(self.media.name(), filename, bytes);
+                if let InputFile::File { filename, source } = media {
+                    multipart = multipart.input_file(
+                        self.media.name(),
+                        filename,
+                        source,
+                    );
                 }
             }
         }
 
         let (boundary, body) = multipart.json("media", &self.media).finish();
 
-        call_method(self.bot, "editMessageMedia", Some(boundary), body).await
+        let mut options = self.options;
+        options.local_files = self.media.has_local_files();
+
+        call_method(self.bot, options, "editMessageMedia", Some(boundary), body)
+            .await
     }
 }
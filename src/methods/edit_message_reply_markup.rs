@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -9,6 +9,7 @@ use crate::{
     },
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Edits the inline keyboard of a message sent by the bot itself.
 ///
@@ -20,6 +21,8 @@ use serde::Serialize;
 pub struct EditMessageReplyMarkup<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     chat_id: ChatId,
     message_id: message::Id,
     reply_markup: inline::Keyboard,
@@ -34,6 +37,7 @@ impl<'a> EditMessageReplyMarkup<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             message_id,
             reply_markup,
@@ -42,10 +46,28 @@ impl<'a> EditMessageReplyMarkup<'a> {
 }
 
 impl EditMessageReplyMarkup<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<Message, errors::MethodCall> {
         call_method(
             self.bot,
+            self.options,
             "editMessageReplyMarkup",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -1,10 +1,11 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
     types::parameters::{ChatId, ImplicitChatId},
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Exports a chat's invite link.
 ///
@@ -16,6 +17,8 @@ use serde::Serialize;
 pub struct ExportChatInviteLink<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     chat_id: ChatId,
 }
 
@@ -23,16 +26,35 @@ impl<'a> ExportChatInviteLink<'a> {
     pub(crate) fn new(bot: &'a InnerBot, chat_id: impl ImplicitChatId) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
         }
     }
 }
 
 impl ExportChatInviteLink<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<String, errors::MethodCall> {
         call_method(
             self.bot,
+            self.options,
             "exportChatInviteLink",
             None,
             serde_json::to_vec(&self).unwrap(),
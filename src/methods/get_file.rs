@@ -1,10 +1,11 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
     types::file::{self, File},
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Gets information about a file.
 ///
@@ -16,20 +17,44 @@ use serde::Serialize;
 pub struct GetFile<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     file_id: file::Id,
 }
 
 impl<'a> GetFile<'a> {
     pub(crate) const fn new(bot: &'a InnerBot, file_id: file::Id) -> Self {
-        Self { bot, file_id }
+        Self {
+            bot,
+            options: CallOptions::new(),
+            file_id,
+        }
     }
 }
 
 impl GetFile<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<File, errors::MethodCall> {
         call_method(
             self.bot,
+            self.options,
             "getFile",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -1,10 +1,11 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
     types::{game::HighScore, user, InlineMessageId},
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Gets an excerpt from the high score table of a game sent via the inline
 /// mode.
@@ -17,6 +18,8 @@ use serde::Serialize;
 pub struct GetInlineGameHighScores<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     user_id: user::Id,
     inline_message_id: InlineMessageId,
 }
@@ -29,6 +32,7 @@ impl<'a> GetInlineGameHighScores<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             user_id,
             inline_message_id,
         }
@@ -36,10 +40,28 @@ impl<'a> GetInlineGameHighScores<'a> {
 }
 
 impl GetInlineGameHighScores<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<Vec<HighScore>, errors::MethodCall> {
         call_method(
             self.bot,
+            self.options,
             "getGameHighScores",
             None,
             serde_json::to_vec(&self).unwrap(),
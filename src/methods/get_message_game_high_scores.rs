@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -10,6 +10,7 @@ use crate::{
     },
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Gets an excerpt from the high score table of a game sent by the bot itself.
 ///
@@ -21,6 +22,8 @@ use serde::Serialize;
 pub struct GetMessageGameHighScores<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     user_id: user::Id,
     chat_id: ChatId,
     message_id: message::Id,
@@ -35,6 +38,7 @@ impl<'a> GetMessageGameHighScores<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             user_id,
             chat_id: chat_id.into(),
             message_id,
@@ -43,10 +47,28 @@ impl<'a> GetMessageGameHighScores<'a> {
 }
 
 impl GetMessageGameHighScores<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<Vec<HighScore>, errors::MethodCall> {
         call_method(
             self.bot,
+            self.options,
             "getGameHighScores",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -1,5 +1,6 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{bot::InnerBot, errors, types::BotCommand};
+use std::time::Duration;
 
 /// Gets the list of the bot's commands.
 ///
@@ -10,17 +11,39 @@ use crate::{bot::InnerBot, errors, types::BotCommand};
 #[must_use = "methods do nothing unless turned into a future"]
 pub struct GetMyCommands<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
 }
 
 impl<'a> GetMyCommands<'a> {
     pub(crate) const fn new(bot: &'a InnerBot) -> Self {
-        Self { bot }
+        Self {
+            bot,
+            options: CallOptions::new(),
+        }
     }
 }
 
 impl GetMyCommands<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<Vec<BotCommand>, errors::MethodCall> {
-        call_method(self.bot, "getMyCommands", None, Vec::new()).await
+        call_method(self.bot, self.options, "getMyCommands", None, Vec::new())
+            .await
     }
 }
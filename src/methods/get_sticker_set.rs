@@ -1,6 +1,7 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{bot::InnerBot, errors, types::sticker};
 use serde::Serialize;
+use std::time::Duration;
 
 /// Gets a sticker set by its name.
 ///
@@ -12,6 +13,8 @@ use serde::Serialize;
 pub struct GetStickerSet<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     name: String,
 }
 
@@ -19,16 +22,35 @@ impl<'a> GetStickerSet<'a> {
     pub(crate) fn new(bot: &'a InnerBot, name: impl Into<String>) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             name: name.into(),
         }
     }
 }
 
 impl GetStickerSet<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<sticker::Set, errors::MethodCall> {
         call_method(
             self.bot,
+            self.options,
             "getStickerSet",
             None,
             serde_json::to_vec(&self).unwrap(),
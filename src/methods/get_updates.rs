@@ -1,16 +1,19 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
     types::{parameters::AllowedUpdates, update::RawUpdate},
 };
 use serde::Serialize;
+use std::time::Duration;
 
 #[derive(Serialize, Debug, Clone)]
 #[must_use]
 pub struct GetUpdates<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     #[serde(skip_serializing_if = "Option::is_none")]
     offset: Option<isize>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -31,6 +34,7 @@ impl<'a> GetUpdates<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             offset,
             limit,
             timeout,
@@ -40,12 +44,30 @@ impl<'a> GetUpdates<'a> {
 }
 
 impl GetUpdates<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub(crate) async fn call(
         self,
     ) -> Result<Vec<RawUpdate>, errors::MethodCall> {
         call_method(
             self.bot,
+            self.options,
             "getUpdates",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -1,6 +1,7 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{bot::InnerBot, errors, types::user};
 use serde::Serialize;
+use std::time::Duration;
 
 /// Gets a user's profile photos.
 ///
@@ -12,6 +13,8 @@ use serde::Serialize;
 pub struct GetUserProfilePhotos<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     user_id: user::Id,
     #[serde(skip_serializing_if = "Option::is_none")]
     offset: Option<u32>,
@@ -23,6 +26,7 @@ impl<'a> GetUserProfilePhotos<'a> {
     pub(crate) const fn new(bot: &'a InnerBot, user_id: user::Id) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             user_id,
             offset: None,
             limit: None,
@@ -45,10 +49,28 @@ impl<'a> GetUserProfilePhotos<'a> {
 }
 
 impl GetUserProfilePhotos<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<user::ProfilePhotos, errors::MethodCall> {
         call_method(
             self.bot,
+            self.options,
             "getUserProfilePhotos",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -0,0 +1,312 @@
+use super::{call_method, CallOptions};
+use crate::{
+    bot::InnerBot,
+    errors,
+    types::{
+        file,
+        input_file::{Attachment, FileSource, InputFile},
+        parameters::{ChatId, ImplicitChatId},
+    },
+    Multipart,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::{borrow::Cow, fmt::Debug, time::Duration};
+
+/// Calls a Bot API method which `tbot` doesn't wrap yet.
+///
+/// The Bot API gains new methods faster than `tbot` does. Until `tbot` wraps
+/// a method, you can call it with this struct: configure its parameters with
+/// [`param`] or [`params`], attach files with [`file`], and choose the type
+/// to deserialize the result into when calling [`call`]. The call is retried,
+/// rate limited and traced just like calls to the methods `tbot` wraps.
+///
+/// ```no_run
+/// # async fn foo() -> Result<(), tbot::errors::MethodCall> {
+/// let bot = tbot::from_env!("BOT_TOKEN");
+///
+/// let is_ok: bool = bot
+///     .raw_method("setChatTitle")
+///     .param("chat_id", -1_001_234_567_890_i64)
+///     .param("title", "tbot")
+///     .call()
+///     .await?;
+/// # Ok(()) }
+/// ```
+///
+/// Files are attached with any of `tbot`'s input file types:
+///
+/// ```no_run
+/// # async fn foo() -> Result<(), tbot::errors::MethodCall> {
+/// use tbot::types::{input_file::Document, message::Message};
+/// # let bot = tbot::from_env!("BOT_TOKEN");
+///
+/// let message: Message = bot
+///     .raw_method("sendDocument")
+///     .param("chat_id", -1_001_234_567_890_i64)
+///     .file("document", Document::with_path("./report.pdf"))
+///     .call()
+///     .await?;
+/// # Ok(()) }
+/// ```
+///
+/// If you upload a file, the call is made with `multipart/form-data`.
+/// In this case, string parameters are sent as is and all other parameters
+/// are serialized to JSON, as the Bot API expects. Files which are already
+/// on Telegram's servers, available by a URL or local to a self-hosted
+/// server are passed as usual string parameters with their ID, URL or URI.
+///
+/// [`param`]: Self::param
+/// [`params`]: Self::params
+/// [`file`]: Self::file
+/// [`call`]: Self::call
+#[derive(Debug, Clone)]
+#[must_use = "methods do nothing unless turned into a future"]
+pub struct RawMethod<'a> {
+    bot: &'a InnerBot,
+    options: CallOptions,
+    name: String,
+    params: Map<String, Value>,
+    attachments: Vec<(String, String, FileSource)>,
+}
+
+impl<'a> RawMethod<'a> {
+    pub(crate) fn new(bot: &'a InnerBot, name: impl Into<String>) -> Self {
+        Self {
+            bot,
+            options: CallOptions::new(),
+            name: name.into(),
+            params: Map::new(),
+            attachments: Vec::new(),
+        }
+    }
+
+    /// Configures a parameter, replacing the parameter's previous value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` can't be serialized to JSON.
+    pub fn param(
+        mut self,
+        name: impl Into<String>,
+        value: impl Serialize,
+    ) -> Self {
+        let value = serde_json::to_value(value).unwrap_or_else(|error| {
+            panic!("[tbot] Failed to serialize a parameter: {:#?}", error)
+        });
+        self.params.insert(name.into(), value);
+        self
+    }
+
+    /// Configures several parameters from a struct or a map at once,
+    /// replacing previous values of the same parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `params` can't be serialized to a JSON object.
+    pub fn params(mut self, params: impl Serialize) -> Self {
+        match serde_json::to_value(params) {
+            Ok(Value::Object(params)) => self.params.extend(params),
+            Ok(params) => panic!(
+                "[tbot] Parameters must serialize to an object, got {}",
+                params
+            ),
+            Err(error) => {
+                panic!("[tbot] Failed to serialize parameters: {:#?}", error)
+            }
+        }
+        self
+    }
+
+    /// Attaches `file` as the parameter `name`.
+    ///
+    /// To refer to an uploaded file from another parameter, e.g. from `media`
+    /// of `sendMediaGroup`, use `attach://<name>`.
+    pub fn file(
+        mut self,
+        name: impl Into<String>,
+        file: impl Into<Attachment>,
+    ) -> Self {
+        let name = name.into();
+        let Attachment(file) = file.into();
+        self.options = self.options.local_file(&file);
+
+        match file {
+            InputFile::File { filename, source } => {
+                self.attachments.push((name, filename, source));
+            }
+            InputFile::Url(file)
+            | InputFile::Id(file::Id(file))
+            | InputFile::Local(file) => {
+                self.params.insert(name, Value::String(file));
+            }
+        }
+
+        self
+    }
+
+    /// Subjects this call to the bot's rate limits for `chat_id`, as if the
+    /// method sent a message to the chat. See [`bot::Builder::rate_limits`]
+    /// for details.
+    ///
+    /// [`bot::Builder::rate_limits`]: crate::bot::Builder::rate_limits
+    pub fn rate_limited(mut self, chat_id: impl ImplicitChatId) -> Self {
+        let chat_id: ChatId = chat_id.into();
+        self.options = self.options.rate_limited(&chat_id);
+        self
+    }
+}
+
+impl RawMethod<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    /// Calls the method and deserializes its result into `T`.
+    pub async fn call<T>(self) -> Result<T, errors::MethodCall>
+    where
+        T: DeserializeOwned + Debug,
+    {
+        if self.attachments.is_empty() {
+            let body = serde_json::to_vec(&self.params).unwrap();
+            return call_method(self.bot, self.options, &self.name, None, body)
+                .await;
+        }
+
+        let parts = self.params.len() + self.attachments.len();
+        let mut multipart = Multipart::new(parts);
+
+        for (name, value) in &self.params {
+            let body = match value {
+                Value::String(value) => Cow::Borrowed(value.as_bytes()),
+                value => Cow::Owned(value.to_string().into_bytes()),
+            };
+            multipart = multipart.part(Cow::Borrowed(name), body);
+        }
+
+        for (name, filename, source) in &self.attachments {
+            multipart = multipart.input_file(name, filename, source);
+        }
+
+        let (boundary, body) = multipart.finish();
+
+        call_method(self.bot, self.options, &self.name, Some(boundary), body)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        connectors::{Transport, TransportError},
+        token::Token,
+        types::input_file::Document,
+    };
+    use futures::future::BoxFuture;
+    use hyper::{header::CONTENT_TYPE, Body, Request, Response};
+    use std::sync::{Arc, Mutex};
+
+    /// Records the content type and the body of every request.
+    #[derive(Debug, Default)]
+    struct Recording(Arc<Mutex<Vec<(String, Vec<u8>)>>>);
+
+    impl Transport for Recording {
+        fn request(
+            &self,
+            request: Request<Body>,
+        ) -> BoxFuture<'static, Result<Response<Body>, TransportError>>
+        {
+            let requests = Arc::clone(&self.0);
+            Box::pin(async move {
+                let content_type =
+                    request.headers()[CONTENT_TYPE].to_str()?.to_owned();
+                let body = hyper::body::to_bytes(request.into_body()).await?;
+                requests.lock().unwrap().push((content_type, body.to_vec()));
+                Ok(Response::new(Body::from(r#"{"ok":true,"result":true}"#)))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_json_without_uploads() {
+        let requests = Arc::default();
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Recording(Arc::clone(&requests)));
+
+        let document = Document::with_id(file::Id(String::from("ID")));
+        let result: bool = RawMethod::new(&bot, "sendDocument")
+            .param("chat_id", 42)
+            .param("caption", "foo")
+            .file("document", document)
+            .call()
+            .await
+            .unwrap();
+        assert!(result);
+
+        let requests = requests.lock().unwrap();
+        let (content_type, body) = &requests[0];
+        assert_eq!(content_type, "application/json");
+        let body: Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "chat_id": 42,
+                "caption": "foo",
+                "document": "ID",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_multipart_with_uploads() {
+        let requests = Arc::default();
+        let mut bot = InnerBot::new(Token(String::from("TOKEN")));
+        bot.set_transport(Recording(Arc::clone(&requests)));
+
+        let document = Document::with_bytes("foo.txt", &b"bar"[..]);
+        let result: bool = RawMethod::new(&bot, "sendDocument")
+            .param("chat_id", 42)
+            .param("caption", "foo")
+            .param("reply_markup", serde_json::json!({ "keyboard": [] }))
+            .file("document", document)
+            .call()
+            .await
+            .unwrap();
+        assert!(result);
+
+        let requests = requests.lock().unwrap();
+        let (content_type, body) = &requests[0];
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let body = String::from_utf8(body.clone()).unwrap();
+        let part = |disposition: &str, value: &str| {
+            format!(
+                "--{}\r\nContent-Disposition: form-data; {}\r\n\r\n{}\r\n",
+                boundary, disposition, value,
+            )
+        };
+
+        assert!(body.contains(&part(r#"name="chat_id""#, "42")));
+        assert!(body.contains(&part(r#"name="caption""#, "foo")));
+        assert!(body
+            .contains(&part(r#"name="reply_markup""#, r#"{"keyboard":[]}"#)));
+        assert!(body
+            .contains(&part(r#"name="document"; filename="foo.txt""#, "bar")));
+        assert!(body.ends_with(&format!("--{}--\r\n", boundary)));
+    }
+}
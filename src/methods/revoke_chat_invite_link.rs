@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -8,6 +8,7 @@ use crate::{
     },
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Revokes an invite link for a chat.
 ///
@@ -19,6 +20,8 @@ use serde::Serialize;
 pub struct RevokeChatInviteLink<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     chat_id: ChatId,
     invite_link: String,
 }
@@ -31,6 +34,7 @@ impl<'a> RevokeChatInviteLink<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             invite_link: link.into(),
         }
@@ -38,10 +42,28 @@ impl<'a> RevokeChatInviteLink<'a> {
 }
 
 impl RevokeChatInviteLink<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<chat::InviteLink, errors::MethodCall> {
         call_method(
             self.bot,
+            self.options,
             "revokeChatInviteLink",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -11,6 +11,7 @@ use crate::{
     },
     Multipart,
 };
+use std::time::Duration;
 
 /// Sends an audio.
 ///
@@ -21,6 +22,7 @@ use crate::{
 #[must_use = "methods do nothing unless turned into a future"]
 pub struct SendAudio<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
     chat_id: ChatId,
     audio: Audio,
     disable_notification: Option<bool>,
@@ -37,6 +39,7 @@ impl<'a> SendAudio<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             audio,
             disable_notification: None,
@@ -77,8 +80,46 @@ impl<'a> SendAudio<'a> {
 }
 
 impl SendAudio<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
-    pub async fn call(self) -> Result<Message, errors::MethodCall> {
+    pub async fn call(mut self) -> Result<Message, errors::MethodCall> {
+        let mut cached =
+            self.bot.use_cached_file_id("audio", &mut self.audio.media);
+
+        let message = match self.send().await {
+            Err(error) => {
+                match self.bot.reject_cached_file_id(&mut cached, &error) {
+                    Some(file) => {
+                        self.audio.media = file;
+                        self.send().await?
+                    }
+                    None => return Err(error),
+                }
+            }
+            result => result?,
+        };
+
+        self.bot.cache_file_id(cached, &message);
+        Ok(message)
+    }
+
+    async fn send(&self) -> Result<Message, errors::MethodCall> {
         let mut multipart = Multipart::new(11)
             .chat_id("chat_id", &self.chat_id)
             .maybe_string("duration", self.audio.duration)
@@ -92,26 +133,37 @@ impl SendAudio<'_> {
                 "allow_sending_without_reply",
                 &self.allow_sending_without_reply,
             )
-            .maybe_json("reply_markup", self.reply_markup);
+            .maybe_json("reply_markup", self.reply_markup.as_ref());
+
+        let mut options = self
+            .options
+            .rate_limited(&self.chat_id)
+            .local_file(&self.audio.media);
 
         match &self.audio.media {
             InputFile::File {
-                filename, bytes, ..
-            } => multipart = multipart.file("audio", filename, bytes),
-            InputFile::Id(file::Id(audio)) | InputFile::Url(audio) => {
+                filename, source, ..
+            } => multipart = multipart.input_file("audio", filename, source),
+            InputFile::Id(file::Id(audio))
+            | InputFile::Url(audio)
+            | InputFile::Local(audio) => {
                 multipart = multipart.str("audio", audio);
             }
         }
 
-        if let Some(Thumb(InputFile::File {
-            filename, bytes, ..
-        })) = &self.audio.thumb
-        {
-            multipart = multipart.file("thumb", filename, bytes);
+        match &self.audio.thumb {
+            Some(Thumb(InputFile::File { filename, source })) => {
+                multipart = multipart.input_file("thumb", filename, source);
+            }
+            Some(Thumb(InputFile::Local(thumb))) => {
+                multipart = multipart.str("thumb", thumb);
+                options.local_files = true;
+            }
+            _ => (),
         }
 
         let (boundary, body) = multipart.finish();
 
-        call_method(self.bot, "sendAudio", Some(boundary), body).await
+        call_method(self.bot, options, "sendAudio", Some(boundary), body).await
     }
 }
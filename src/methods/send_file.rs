@@ -0,0 +1,175 @@
+use super::{
+    CallOptions, SendAnimation, SendAudio, SendDocument, SendPhoto, SendVideo,
+    SendVoice,
+};
+use crate::{
+    bot::InnerBot,
+    errors,
+    types::{
+        input_file::{
+            detect_kind, Animation, Audio, Document, FileKind, InputFile,
+            Photo, Video, Voice,
+        },
+        keyboard,
+        message::{self, Message},
+        parameters::{ChatId, ImplicitChatId, Text},
+    },
+};
+use std::time::Duration;
+
+/// Sends a file as the kind of media it turns out to be.
+///
+/// `tbot` looks at the file's first bytes and its extension to choose between
+/// [`SendPhoto`], [`SendVideo`], [`SendAnimation`], [`SendAudio`],
+/// [`SendVoice`] and [`SendDocument`]. If the filename lacks an extension
+/// matching the detected format, `tbot` appends one, so that Telegram infers
+/// the right MIME type. Files in formats the Bot API can't send as media
+/// and photos over 10MB are sent as documents. Use [`kind`] to find out what
+/// the file is sent as.
+///
+/// Other limits aren't checked, e.g. the 50MB upload limit of the cloud
+/// Bot API, so sending a file exceeding them fails as with any other method.
+///
+/// [`kind`]: Self::kind
+#[derive(Debug, Clone)]
+#[must_use = "methods do nothing unless turned into a future"]
+pub struct SendFile<'a> {
+    bot: &'a InnerBot,
+    options: CallOptions,
+    chat_id: ChatId,
+    kind: FileKind,
+    media: InputFile,
+    caption: Option<Text>,
+    disable_notification: Option<bool>,
+    reply_to_message_id: Option<message::Id>,
+    allow_sending_without_reply: bool,
+    reply_markup: Option<keyboard::Any>,
+}
+
+impl<'a> SendFile<'a> {
+    pub(crate) fn new(
+        bot: &'a InnerBot,
+        chat_id: impl ImplicitChatId,
+        bytes: impl Into<Vec<u8>>,
+        filename: impl Into<String>,
+    ) -> Self {
+        let bytes = bytes.into();
+        let (kind, filename) = detect_kind(&bytes, filename.into());
+
+        Self {
+            bot,
+            options: CallOptions::new(),
+            chat_id: chat_id.into(),
+            kind,
+            media: InputFile::with_bytes(filename, bytes),
+            caption: None,
+            disable_notification: None,
+            reply_to_message_id: None,
+            allow_sending_without_reply: false,
+            reply_markup: None,
+        }
+    }
+
+    /// Returns the kind of media the file is sent as.
+    #[must_use]
+    pub const fn kind(&self) -> FileKind {
+        self.kind
+    }
+
+    /// Configures the file's caption.
+    pub fn caption(mut self, caption: impl Into<Text>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// Configures whether the message is sent silently.
+    /// Reflects the `disable_notification` parameter.
+    pub const fn is_notification_disabled(mut self, is_disabled: bool) -> Self {
+        self.disable_notification = Some(is_disabled);
+        self
+    }
+
+    /// Configures which message this file is sent in reply to.
+    /// Reflects the `reply_to_message_id` parameter.
+    pub const fn in_reply_to(mut self, id: message::Id) -> Self {
+        self.reply_to_message_id = Some(id);
+        self
+    }
+
+    /// Configures whether this message should be sent even
+    /// if the replied-to message is not found.
+    /// Reflects the `allow_sending_without_reply` parameter.
+    pub const fn allow_sending_without_reply(mut self) -> Self {
+        self.allow_sending_without_reply = true;
+        self
+    }
+
+    /// Configures a keyboard for the message.
+    /// Reflects the `reply_markup` parameter.
+    pub fn reply_markup(mut self, markup: impl Into<keyboard::Any>) -> Self {
+        self.reply_markup = Some(markup.into());
+        self
+    }
+}
+
+impl SendFile<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    /// Calls the method.
+    pub async fn call(self) -> Result<Message, errors::MethodCall> {
+        macro_rules! send {
+            ($method:ident, $media:ident) => {{
+                let mut media = $media::new(self.media);
+                if let Some(caption) = self.caption {
+                    media = media.caption(caption);
+                }
+
+                let mut method = $method::new(self.bot, self.chat_id, media);
+                if let Some(is_disabled) = self.disable_notification {
+                    method = method.is_notification_disabled(is_disabled);
+                }
+                if let Some(id) = self.reply_to_message_id {
+                    method = method.in_reply_to(id);
+                }
+                if self.allow_sending_without_reply {
+                    method = method.allow_sending_without_reply();
+                }
+                if let Some(markup) = self.reply_markup {
+                    method = method.reply_markup(markup);
+                }
+                if self.options.disable_retries {
+                    method = method.without_retries();
+                }
+                if let Some(timeout) = self.options.timeout {
+                    method = method.timeout(timeout);
+                }
+
+                method.call().await
+            }};
+        }
+
+        match self.kind {
+            FileKind::Photo => send!(SendPhoto, Photo),
+            FileKind::Video => send!(SendVideo, Video),
+            FileKind::Animation => send!(SendAnimation, Animation),
+            FileKind::Audio => send!(SendAudio, Audio),
+            FileKind::Voice => send!(SendVoice, Voice),
+            FileKind::Document => send!(SendDocument, Document),
+        }
+    }
+}
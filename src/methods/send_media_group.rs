@@ -1,17 +1,18 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
     types::{
         input_file::{
             Album, AnyGroupMedia, Audio, Document, InputFile, MediaGroup,
-            Photo, Thumb, Video,
+            Photo, PhotoOrVideo, Thumb, Video,
         },
         message::{self, Message},
         parameters::{ChatId, ImplicitChatId},
     },
     Multipart,
 };
+use std::time::Duration;
 
 /// Sends an album.
 ///
@@ -22,6 +23,7 @@ use crate::{
 #[must_use = "methods do nothing unless turned into a future"]
 pub struct SendMediaGroup<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
     chat_id: ChatId,
     media: MediaGroup,
     disable_notification: Option<bool>,
@@ -37,6 +39,7 @@ impl<'a> SendMediaGroup<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             media: media.into(),
             disable_notification: None,
@@ -69,8 +72,60 @@ impl<'a> SendMediaGroup<'a> {
 }
 
 impl SendMediaGroup<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
-    pub async fn call(self) -> Result<Vec<Message>, errors::MethodCall> {
+    pub async fn call(mut self) -> Result<Vec<Message>, errors::MethodCall> {
+        let bot = self.bot;
+        let mut cached: Vec<_> = files(&mut self.media)
+            .into_iter()
+            .map(|(kind, file)| bot.use_cached_file_id(kind, file))
+            .collect();
+
+        let messages = match self.send().await {
+            Err(error) => {
+                let mut has_rejected_ids = false;
+                let files = files(&mut self.media);
+                for ((_, file), cached) in files.into_iter().zip(&mut cached) {
+                    if let Some(original) =
+                        self.bot.reject_cached_file_id(cached, &error)
+                    {
+                        *file = original;
+                        has_rejected_ids = true;
+                    }
+                }
+
+                if !has_rejected_ids {
+                    return Err(error);
+                }
+                self.send().await?
+            }
+            result => result?,
+        };
+
+        for (cached, message) in cached.into_iter().zip(&messages) {
+            self.bot.cache_file_id(cached, message);
+        }
+
+        Ok(messages)
+    }
+
+    async fn send(&self) -> Result<Vec<Message>, errors::MethodCall> {
         let mut multipart = Multipart::new(4 + self.media.len())
             .chat_id("chat_id", &self.chat_id)
             .maybe_string("disabled_notification", self.disable_notification)
@@ -80,60 +135,67 @@ impl SendMediaGroup<'_> {
                 &self.allow_sending_without_reply,
             );
 
-        let album = Album(self.media);
+        let album = Album(&self.media);
 
         macro_rules! add_thumb {
             ($thumb:expr, $index:expr) => {
-                if let Some(Thumb(InputFile::File { filename, bytes })) = $thumb
+                if let Some(Thumb(InputFile::File { filename, source })) =
+                    $thumb
                 {
                     let name = format!("thumb_{}", $index);
-                    multipart =
-                        multipart.file_owned_name(name, &filename, &bytes)
+                    multipart = multipart
+                        .input_file_owned_name(name, &filename, &source)
                 }
             };
         }
 
+        let mut options = self.options.rate_limited(&self.chat_id);
+
         for (index, media) in album.0.iter().enumerate() {
+            if media.has_local_files() {
+                options.local_files = true;
+            }
+
             match media {
                 AnyGroupMedia::Photo(Photo {
-                    media: InputFile::File { filename, bytes },
+                    media: InputFile::File { filename, source },
                     ..
                 }) => {
                     let name = format!("photo_{}", index);
 
                     multipart =
-                        multipart.file_owned_name(name, filename, bytes);
+                        multipart.input_file_owned_name(name, filename, source);
                 }
                 AnyGroupMedia::Video(Video {
-                    media: InputFile::File { filename, bytes },
+                    media: InputFile::File { filename, source },
                     thumb,
                     ..
                 }) => {
                     let name = format!("video_{}", index);
                     multipart =
-                        multipart.file_owned_name(name, filename, bytes);
+                        multipart.input_file_owned_name(name, filename, source);
 
                     add_thumb!(thumb, index);
                 }
                 AnyGroupMedia::Audio(Audio {
-                    media: InputFile::File { filename, bytes },
+                    media: InputFile::File { filename, source },
                     thumb,
                     ..
                 }) => {
                     let name = format!("audio_{}", index);
                     multipart =
-                        multipart.file_owned_name(name, filename, bytes);
+                        multipart.input_file_owned_name(name, filename, source);
 
                     add_thumb!(thumb, index);
                 }
                 AnyGroupMedia::Document(Document {
-                    media: InputFile::File { filename, bytes },
+                    media: InputFile::File { filename, source },
                     thumb,
                     ..
                 }) => {
                     let name = format!("document_{}", index);
                     multipart =
-                        multipart.file_owned_name(name, filename, bytes);
+                        multipart.input_file_owned_name(name, filename, source);
 
                     add_thumb!(thumb, index);
                 }
@@ -144,8 +206,30 @@ impl SendMediaGroup<'_> {
             }
         }
 
-        let (boundary, body) = multipart.json("media", &album).finish();
+        let (boundary, body) = multipart.json("media", album).finish();
+
+        call_method(self.bot, options, "sendMediaGroup", Some(boundary), body)
+            .await
+    }
+}
 
-        call_method(self.bot, "sendMediaGroup", Some(boundary), body).await
+/// Lists the group's files along with the kinds they're cached as.
+fn files(media: &mut MediaGroup) -> Vec<(&'static str, &mut InputFile)> {
+    match media {
+        MediaGroup::PhotosAndVideos(album) => album
+            .iter_mut()
+            .map(|media| match media {
+                PhotoOrVideo::Photo(photo) => ("photo", &mut photo.media),
+                PhotoOrVideo::Video(video) => ("video", &mut video.media),
+            })
+            .collect(),
+        MediaGroup::Audios(audios) => audios
+            .iter_mut()
+            .map(|audio| ("audio", &mut audio.media))
+            .collect(),
+        MediaGroup::Documents(documents) => documents
+            .iter_mut()
+            .map(|document| ("document", &mut document.media))
+            .collect(),
     }
 }
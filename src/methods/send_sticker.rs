@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -11,6 +11,7 @@ use crate::{
     },
     Multipart,
 };
+use std::time::Duration;
 
 /// Sends a sticker.
 ///
@@ -21,6 +22,7 @@ use crate::{
 #[must_use = "methods do nothing unless turned into a future"]
 pub struct SendSticker<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
     chat_id: ChatId,
     sticker: Sticker,
     disable_notification: Option<bool>,
@@ -37,6 +39,7 @@ impl<'a> SendSticker<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             sticker,
             disable_notification: None,
@@ -77,8 +80,47 @@ impl<'a> SendSticker<'a> {
 }
 
 impl SendSticker<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
-    pub async fn call(self) -> Result<Message, errors::MethodCall> {
+    pub async fn call(mut self) -> Result<Message, errors::MethodCall> {
+        let mut cached = self
+            .bot
+            .use_cached_file_id("sticker", &mut self.sticker.media);
+
+        let message = match self.send().await {
+            Err(error) => {
+                match self.bot.reject_cached_file_id(&mut cached, &error) {
+                    Some(file) => {
+                        self.sticker.media = file;
+                        self.send().await?
+                    }
+                    None => return Err(error),
+                }
+            }
+            result => result?,
+        };
+
+        self.bot.cache_file_id(cached, &message);
+        Ok(message)
+    }
+
+    async fn send(&self) -> Result<Message, errors::MethodCall> {
         let mut multipart = Multipart::new(5)
             .chat_id("chat_id", &self.chat_id)
             .maybe_string("disabled_notification", self.disable_notification)
@@ -87,19 +129,30 @@ impl SendSticker<'_> {
                 "allow_sending_without_reply",
                 &self.allow_sending_without_reply,
             )
-            .maybe_json("reply_markup", self.reply_markup);
+            .maybe_json("reply_markup", self.reply_markup.as_ref());
 
         match &self.sticker.media {
             InputFile::File {
-                filename, bytes, ..
-            } => multipart = multipart.file("sticker", filename, bytes),
-            InputFile::Id(file::Id(sticker)) | InputFile::Url(sticker) => {
+                filename, source, ..
+            } => multipart = multipart.input_file("sticker", filename, source),
+            InputFile::Id(file::Id(sticker))
+            | InputFile::Url(sticker)
+            | InputFile::Local(sticker) => {
                 multipart = multipart.str("sticker", sticker);
             }
         }
 
         let (boundary, body) = multipart.finish();
 
-        call_method(self.bot, "sendSticker", Some(boundary), body).await
+        call_method(
+            self.bot,
+            self.options
+                .rate_limited(&self.chat_id)
+                .local_file(&self.sticker.media),
+            "sendSticker",
+            Some(boundary),
+            body,
+        )
+        .await
     }
 }
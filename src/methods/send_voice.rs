@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -11,6 +11,7 @@ use crate::{
     },
     Multipart,
 };
+use std::time::Duration;
 
 /// Sends a voice.
 ///
@@ -21,6 +22,7 @@ use crate::{
 #[must_use = "methods do nothing unless turned into a future"]
 pub struct SendVoice<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
     chat_id: ChatId,
     voice: Voice,
     disable_notification: Option<bool>,
@@ -37,6 +39,7 @@ impl<'a> SendVoice<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             voice,
             disable_notification: None,
@@ -77,8 +80,46 @@ impl<'a> SendVoice<'a> {
 }
 
 impl SendVoice<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
-    pub async fn call(self) -> Result<Message, errors::MethodCall> {
+    pub async fn call(mut self) -> Result<Message, errors::MethodCall> {
+        let mut cached =
+            self.bot.use_cached_file_id("voice", &mut self.voice.media);
+
+        let message = match self.send().await {
+            Err(error) => {
+                match self.bot.reject_cached_file_id(&mut cached, &error) {
+                    Some(file) => {
+                        self.voice.media = file;
+                        self.send().await?
+                    }
+                    None => return Err(error),
+                }
+            }
+            result => result?,
+        };
+
+        self.bot.cache_file_id(cached, &message);
+        Ok(message)
+    }
+
+    async fn send(&self) -> Result<Message, errors::MethodCall> {
         let mut multipart = Multipart::new(8)
             .chat_id("chat_id", &self.chat_id)
             .maybe_string("duration", self.voice.duration)
@@ -90,19 +131,30 @@ impl SendVoice<'_> {
                 "allow_sending_without_reply",
                 &self.allow_sending_without_reply,
             )
-            .maybe_json("reply_markup", self.reply_markup);
+            .maybe_json("reply_markup", self.reply_markup.as_ref());
 
         match &self.voice.media {
             InputFile::File {
-                filename, bytes, ..
-            } => multipart = multipart.file("voice", filename, bytes),
-            InputFile::Id(file::Id(voice)) | InputFile::Url(voice) => {
+                filename, source, ..
+            } => multipart = multipart.input_file("voice", filename, source),
+            InputFile::Id(file::Id(voice))
+            | InputFile::Url(voice)
+            | InputFile::Local(voice) => {
                 multipart = multipart.str("voice", voice);
             }
         }
 
         let (boundary, body) = multipart.finish();
 
-        call_method(self.bot, "sendVoice", Some(boundary), body).await
+        call_method(
+            self.bot,
+            self.options
+                .rate_limited(&self.chat_id)
+                .local_file(&self.voice.media),
+            "sendVoice",
+            Some(boundary),
+            body,
+        )
+        .await
     }
 }
@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -8,6 +8,7 @@ use crate::{
     },
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Sets a custom title for an admin in a supergroup promoted by the bot.
 ///
@@ -19,6 +20,8 @@ use serde::Serialize;
 pub struct SetChatAdministratorCustomTitle<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     chat_id: ChatId,
     user_id: user::Id,
     custom_title: String,
@@ -33,6 +36,7 @@ impl<'a> SetChatAdministratorCustomTitle<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             user_id,
             custom_title: custom_title.into(),
@@ -41,10 +45,28 @@ impl<'a> SetChatAdministratorCustomTitle<'a> {
 }
 
 impl SetChatAdministratorCustomTitle<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "setChatAdministratorCustomTitle",
             None,
             serde_json::to_vec(&self).unwrap(),
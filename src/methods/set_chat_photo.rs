@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -8,6 +8,7 @@ use crate::{
     },
     Multipart,
 };
+use std::time::Duration;
 
 /// Sets a chat's photo.
 ///
@@ -18,6 +19,7 @@ use crate::{
 #[must_use = "methods do nothing unless turned into a future"]
 pub struct SetChatPhoto<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
     chat_id: ChatId,
     photo: ChatPhoto,
 }
@@ -30,6 +32,7 @@ impl<'a> SetChatPhoto<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             chat_id: chat_id.into(),
             photo,
         }
@@ -37,6 +40,23 @@ impl<'a> SetChatPhoto<'a> {
 }
 
 impl SetChatPhoto<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         let chat_id = match self.chat_id {
@@ -46,17 +66,26 @@ impl SetChatPhoto<'_> {
 
         let mut multipart = Multipart::new(2).str("chat_id", &chat_id);
 
-        if let InputFile::File {
-            filename, bytes, ..
-        } = &self.photo.0
-        {
-            multipart = multipart.file("photo", filename, bytes);
+        match &self.photo.0 {
+            InputFile::File { filename, source } => {
+                multipart = multipart.input_file("photo", filename, source);
+            }
+            InputFile::Local(photo) => {
+                multipart = multipart.str("photo", photo);
+            }
+            InputFile::Url(..) | InputFile::Id(..) => (),
         }
 
         let (boundary, body) = multipart.finish();
 
-        call_method::<bool>(self.bot, "setChatPhoto", Some(boundary), body)
-            .await?;
+        call_method::<bool>(
+            self.bot,
+            self.options.local_file(&self.photo.0),
+            "setChatPhoto",
+            Some(boundary),
+            body,
+        )
+        .await?;
 
         Ok(())
     }
@@ -1,6 +1,7 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{bot::InnerBot, errors, types::BotCommand};
 use serde::Serialize;
+use std::time::Duration;
 
 /// Sets the list of the bot's commands.
 ///
@@ -12,6 +13,8 @@ use serde::Serialize;
 pub struct SetMyCommands<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     commands: Vec<BotCommand>,
 }
 
@@ -22,16 +25,35 @@ impl<'a> SetMyCommands<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             commands: commands.into(),
         }
     }
 }
 
 impl SetMyCommands<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "setMyCommands",
             None,
             serde_json::to_vec(&self).unwrap(),
@@ -1,10 +1,11 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
     types::{passport, user},
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Reports passport errors to the user.
 ///
@@ -16,6 +17,8 @@ use serde::Serialize;
 pub struct SetPassportDataErrors<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     user_id: user::Id,
     errors: Vec<passport::element::Error>,
 }
@@ -28,6 +31,7 @@ impl<'a> SetPassportDataErrors<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             user_id,
             errors: errors.into(),
         }
@@ -35,10 +39,28 @@ impl<'a> SetPassportDataErrors<'a> {
 }
 
 impl SetPassportDataErrors<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "setPassportDataErrors",
             None,
             serde_json::to_vec(&self).unwrap(),
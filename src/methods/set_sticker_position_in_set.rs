@@ -1,6 +1,7 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{bot::InnerBot, errors};
 use serde::Serialize;
+use std::time::Duration;
 
 /// Changes a sticker's position in a sticker set.
 ///
@@ -12,6 +13,8 @@ use serde::Serialize;
 pub struct SetStickerPositionInSet<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     sticker: String,
     position: u32,
 }
@@ -24,6 +27,7 @@ impl<'a> SetStickerPositionInSet<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             sticker: sticker.into(),
             position,
         }
@@ -31,10 +35,28 @@ impl<'a> SetStickerPositionInSet<'a> {
 }
 
 impl SetStickerPositionInSet<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "setStickerPositionInSet",
             None,
             serde_json::to_vec(&self).unwrap(),
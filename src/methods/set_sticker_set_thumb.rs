@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
@@ -9,6 +9,7 @@ use crate::{
     },
     Multipart,
 };
+use std::time::Duration;
 
 /// Sets the thumb of a sticker set.
 ///
@@ -19,6 +20,7 @@ use crate::{
 #[must_use = "methods do nothing unless turned into a future"]
 pub struct SetStickerSetThumb<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
     user_id: user::Id,
     name: String,
     thumb: Option<StickerSetThumb>,
@@ -33,6 +35,7 @@ impl<'a> SetStickerSetThumb<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             user_id,
             name: name.into(),
             thumb,
@@ -41,18 +44,43 @@ impl<'a> SetStickerSetThumb<'a> {
 }
 
 impl SetStickerSetThumb<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         let mut multipart = Multipart::new(3)
             .string("user_id", &self.user_id)
             .str("name", &self.name);
 
+        let mut options = self.options;
+
         if let Some(thumb) = &self.thumb {
+            options = options.local_file(&thumb.media);
+
             match &thumb.media {
                 InputFile::File {
-                    filename, bytes, ..
-                } => multipart = multipart.file("thumb", filename, bytes),
-                InputFile::Id(file::Id(sticker)) | InputFile::Url(sticker) => {
+                    filename, source, ..
+                } => {
+                    multipart = multipart.input_file("thumb", filename, source);
+                }
+                InputFile::Id(file::Id(sticker))
+                | InputFile::Url(sticker)
+                | InputFile::Local(sticker) => {
                     multipart = multipart.str("thumb", sticker);
                 }
             }
@@ -62,6 +90,7 @@ impl SetStickerSetThumb<'_> {
 
         call_method::<bool>(
             self.bot,
+            options,
             "setStickerSetThumb",
             Some(boundary),
             body,
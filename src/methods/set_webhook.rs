@@ -1,4 +1,4 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot, errors, types::parameters::AllowedUpdates, Multipart,
 };
@@ -9,6 +9,7 @@ use std::{net::IpAddr, num::NonZeroU32};
 #[must_use]
 pub struct SetWebhook<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
     url: &'a str,
     ip_address: Option<IpAddr>,
     certificate: Option<&'a str>,
@@ -29,6 +30,7 @@ impl<'a> SetWebhook<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             url,
             ip_address,
             certificate,
@@ -59,8 +61,14 @@ impl SetWebhook<'_> {
 
         let (boundary, body) = multipart.finish();
 
-        call_method::<bool>(self.bot, "setWebhook", Some(boundary), body)
-            .await?;
+        call_method::<bool>(
+            self.bot,
+            self.options,
+            "setWebhook",
+            Some(boundary),
+            body,
+        )
+        .await?;
 
         Ok(())
     }
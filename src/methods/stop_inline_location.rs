@@ -1,10 +1,11 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
     types::{keyboard::inline, InlineMessageId},
 };
 use serde::Serialize;
+use std::time::Duration;
 
 /// Stops a live location sent via the inline mode.
 ///
@@ -16,6 +17,8 @@ use serde::Serialize;
 pub struct StopInlineLocation<'a> {
     #[serde(skip)]
     bot: &'a InnerBot,
+    #[serde(skip)]
+    options: CallOptions,
     inline_message_id: InlineMessageId,
     #[serde(skip_serializing_if = "Option::is_none")]
     reply_markup: Option<inline::Keyboard>,
@@ -28,6 +31,7 @@ impl<'a> StopInlineLocation<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             inline_message_id,
             reply_markup: None,
         }
@@ -43,10 +47,28 @@ impl<'a> StopInlineLocation<'a> {
 }
 
 impl StopInlineLocation<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<(), errors::MethodCall> {
         call_method::<bool>(
             self.bot,
+            self.options,
             "stopMessageLiveLocation",
             None,
             serde_json::to_vec(&self).unwrap(),
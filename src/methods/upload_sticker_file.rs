@@ -1,10 +1,11 @@
-use super::call_method;
+use super::{call_method, CallOptions};
 use crate::{
     bot::InnerBot,
     errors,
     types::{user, File},
     Multipart,
 };
+use std::time::Duration;
 
 /// Uploads a sticker file.
 ///
@@ -15,6 +16,7 @@ use crate::{
 #[must_use = "methods do nothing unless turned into a future"]
 pub struct UploadStickerFile<'a> {
     bot: &'a InnerBot,
+    options: CallOptions,
     user_id: user::Id,
     png_sticker: Vec<u8>,
 }
@@ -27,6 +29,7 @@ impl<'a> UploadStickerFile<'a> {
     ) -> Self {
         Self {
             bot,
+            options: CallOptions::new(),
             user_id,
             png_sticker: png_sticker.into(),
         }
@@ -34,6 +37,23 @@ impl<'a> UploadStickerFile<'a> {
 }
 
 impl UploadStickerFile<'_> {
+    /// Disables retrying this call even if the bot has a retry policy.
+    /// See [`bot::Builder::retry_policy`] for details.
+    ///
+    /// [`bot::Builder::retry_policy`]: crate::bot::Builder::retry_policy
+    pub const fn without_retries(mut self) -> Self {
+        self.options.disable_retries = true;
+        self
+    }
+
+    /// Configures for how long `tbot` waits for the Bot API to respond to
+    /// this call, overriding the bot's default timeout. If the timeout passes,
+    /// the call fails with [`errors::MethodCall::Timeout`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
     /// Calls the method.
     pub async fn call(self) -> Result<File, errors::MethodCall> {
         let (boundary, body) = Multipart::new(2)
@@ -41,6 +61,13 @@ impl UploadStickerFile<'_> {
             .file("png_sticker", "sticker.png", &self.png_sticker)
             .finish();
 
-        call_method(self.bot, "uploadStickerFile", Some(boundary), body).await
+        call_method(
+            self.bot,
+            self.options,
+            "uploadStickerFile",
+            Some(boundary),
+            body,
+        )
+        .await
     }
 }
@@ -0,0 +1,87 @@
+use crate::internal::Sealed;
+use serde::Serialize;
+
+#[allow(clippy::wildcard_imports)]
+use super::*;
+
+/// A method that can be sent in the response to a webhook request.
+///
+/// The Bot API allows replying to a webhook request with a method call, which
+/// saves a round trip to the Bot API server. However, you cannot know whether
+/// such a call succeeded or get its result, so only methods for which you
+/// don't need the result implement this trait.
+///
+/// See [`Bot::reply_via_webhook`] on how to send such a reply.
+///
+/// [`Bot::reply_via_webhook`]: crate::Bot::reply_via_webhook
+pub trait WebhookReply: Serialize + Sealed {
+    /// The name of the method in the Bot API.
+    const NAME: &'static str;
+}
+
+macro_rules! webhook_reply {
+    ($($method:ident => $name:literal,)+) => {
+        $(
+            impl Sealed for $method<'_> {}
+
+            impl WebhookReply for $method<'_> {
+                const NAME: &'static str = $name;
+            }
+        )+
+    };
+}
+
+webhook_reply! {
+    AnswerCallbackQuery => "answerCallbackQuery",
+    AnswerInlineQuery => "answerInlineQuery",
+    AnswerPreCheckoutQuery => "answerPreCheckoutQuery",
+    AnswerShippingQuery => "answerShippingQuery",
+    BanChatMember => "banChatMember",
+    CopyMessage => "copyMessage",
+    CreateChatInviteLink => "createChatInviteLink",
+    DeleteChatPhoto => "deleteChatPhoto",
+    DeleteChatStickerSet => "deleteChatStickerSet",
+    DeleteMessage => "deleteMessage",
+    DeleteStickerFromSet => "deleteStickerFromSet",
+    EditChatInviteLink => "editChatInviteLink",
+    EditInlineCaption => "editMessageCaption",
+    EditInlineLocation => "editMessageLiveLocation",
+    EditInlineReplyMarkup => "editMessageReplyMarkup",
+    EditInlineText => "editMessageText",
+    EditMessageCaption => "editMessageCaption",
+    EditMessageLocation => "editMessageLiveLocation",
+    EditMessageReplyMarkup => "editMessageReplyMarkup",
+    EditMessageText => "editMessageText",
+    ExportChatInviteLink => "exportChatInviteLink",
+    ForwardMessage => "forwardMessage",
+    LeaveChat => "leaveChat",
+    PinChatMessage => "pinChatMessage",
+    PromoteChatMember => "promoteChatMember",
+    RestrictChatMember => "restrictChatMember",
+    RevokeChatInviteLink => "revokeChatInviteLink",
+    SendChatAction => "sendChatAction",
+    SendContact => "sendContact",
+    SendDice => "sendDice",
+    SendGame => "sendGame",
+    SendInvoice => "sendInvoice",
+    SendLocation => "sendLocation",
+    SendMessage => "sendMessage",
+    SendPoll => "sendPoll",
+    SendVenue => "sendVenue",
+    SetChatAdministratorCustomTitle => "setChatAdministratorCustomTitle",
+    SetChatDescription => "setChatDescription",
+    SetChatPermissions => "setChatPermissions",
+    SetChatStickerSet => "setChatStickerSet",
+    SetChatTitle => "setChatTitle",
+    SetInlineGameScore => "setGameScore",
+    SetMessageGameScore => "setGameScore",
+    SetMyCommands => "setMyCommands",
+    SetPassportDataErrors => "setPassportDataErrors",
+    SetStickerPositionInSet => "setStickerPositionInSet",
+    StopInlineLocation => "stopMessageLiveLocation",
+    StopMessageLocation => "stopMessageLiveLocation",
+    StopPoll => "stopPoll",
+    UnbanChatMember => "unbanChatMember",
+    UnpinAllChatMessages => "unpinAllChatMessages",
+    UnpinChatMessage => "unpinChatMessage",
+}
@@ -1,9 +1,42 @@
-use crate::types::parameters::ChatId;
+use crate::types::{
+    chat,
+    input_file::{decode_local_uri, FileSource, LOCAL_URI_SCHEME},
+    parameters::ChatId,
+};
+use futures::{
+    channel::oneshot,
+    future,
+    stream::{self, BoxStream, StreamExt},
+};
+use hyper::body::{Body, Bytes};
 use serde::Serialize;
-use std::{borrow::Cow, collections::HashSet, iter::repeat};
+use std::{
+    borrow::Cow,
+    collections::{hash_map::RandomState, HashSet},
+    fmt::Write,
+    hash::{BuildHasher, Hasher},
+    io,
+    iter::repeat,
+    mem,
+    ops::Range,
+    path::PathBuf,
+    str::from_utf8,
+    task::Poll,
+};
+use tokio::{
+    fs,
+    io::{AsyncRead, AsyncReadExt},
+};
+
+/// How many bytes are read from a streamed file at once.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Resolves once the whole body has been read, or once the body is dropped
+/// because the request ended early.
+pub type Uploaded = oneshot::Receiver<()>;
 
 enum Header<'a> {
-    Field(&'static str),
+    Field(Cow<'a, str>),
     File {
         name: Cow<'a, str>,
         filename: &'a str,
@@ -21,9 +54,309 @@ impl<'a> Header<'a> {
     }
 }
 
+enum PartBody<'a> {
+    InMemory(Cow<'a, [u8]>),
+    /// A file which is streamed rather than read into memory.
+    Streamed(&'a FileSource),
+}
+
+impl PartBody<'_> {
+    const fn is_streamed(&self) -> bool {
+        matches!(self, Self::Streamed(_))
+    }
+
+    /// Streamed parts are never encoded in memory, so their body is empty.
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Self::InMemory(bytes) => bytes,
+            Self::Streamed(_) => &[],
+        }
+    }
+}
+
 struct Part<'a> {
     header: Header<'a>,
-    body: Cow<'a, [u8]>,
+    body: PartBody<'a>,
+}
+
+impl Part<'_> {
+    fn write_header(&self, body: &mut Vec<u8>, boundary: &[u8]) {
+        body.extend_from_slice(b"--");
+        body.extend_from_slice(boundary);
+        body.extend_from_slice(b"\r\n");
+
+        body.extend_from_slice(b"Content-Disposition: form-data; ");
+        body.extend_from_slice(self.header.content_disposition().as_bytes());
+        body.extend_from_slice(b"\r\n\r\n");
+    }
+}
+
+/// A segment of a streamed body.
+#[derive(Debug, Clone)]
+pub enum Segment {
+    Bytes(Bytes),
+    File(FileSource),
+}
+
+/// The body of a method call.
+#[derive(Debug, Clone)]
+pub enum RequestBody {
+    InMemory(Bytes),
+    /// A multipart body with files which are read only when the body is sent.
+    Streamed(Vec<Segment>),
+}
+
+impl From<Vec<u8>> for RequestBody {
+    fn from(body: Vec<u8>) -> Self {
+        Self::InMemory(body.into())
+    }
+}
+
+pub fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Finds where the value of the multipart field `name` starts and ends.
+pub fn find_field(
+    boundary: &str,
+    body: &[u8],
+    name: &str,
+) -> Option<Range<usize>> {
+    let header = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n",
+        boundary, name,
+    );
+    let start = find(body, header.as_bytes())? + header.len();
+    let end = format!("\r\n--{}", boundary);
+    let end = start + find(&body[start..], end.as_bytes())?;
+
+    Some(start..end)
+}
+
+fn read_chunks<R>(reader: R) -> BoxStream<'static, io::Result<Bytes>>
+where
+    R: AsyncRead + Send + Unpin + 'static,
+{
+    let chunks = stream::try_unfold(reader, |mut reader| async move {
+        let mut buffer = vec![0; CHUNK_SIZE];
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+
+        buffer.truncate(read);
+        Ok(Some((Bytes::from(buffer), reader)))
+    });
+
+    chunks.boxed()
+}
+
+impl RequestBody {
+    pub fn in_memory(&self) -> Option<&[u8]> {
+        match self {
+            Self::InMemory(body) => Some(body),
+            Self::Streamed(_) => None,
+        }
+    }
+
+    /// Finds the value of the multipart field `name`. Only files are
+    /// streamed, so fields can be found in streamed bodies as well.
+    pub fn field(&self, boundary: &str, name: &str) -> Option<&[u8]> {
+        fn find_in<'a>(
+            boundary: &str,
+            body: &'a [u8],
+            name: &str,
+        ) -> Option<&'a [u8]> {
+            find_field(boundary, body, name).map(|range| &body[range])
+        }
+
+        match self {
+            Self::InMemory(body) => find_in(boundary, body, name),
+            Self::Streamed(segments) => {
+                segments.iter().find_map(|segment| match segment {
+                    Segment::Bytes(bytes) => find_in(boundary, bytes, name),
+                    Segment::File(_) => None,
+                })
+            }
+        }
+    }
+
+    /// Finds the paths of the local files which the body refers to with
+    /// their `file://` URIs.
+    pub fn local_paths(&self) -> Vec<PathBuf> {
+        fn find_in(body: &[u8], paths: &mut Vec<PathBuf>) {
+            let mut rest = body;
+            while let Some(start) = find(rest, LOCAL_URI_SCHEME) {
+                // URIs are whole values, either JSON strings or fields.
+                let is_value = start > 0 && b"\"\n".contains(&rest[start - 1]);
+                let uri = &rest[start..];
+                let length = uri[LOCAL_URI_SCHEME.len()..]
+                    .iter()
+                    .position(|byte| {
+                        !byte.is_ascii_alphanumeric()
+                            && !b"/-._~%".contains(byte)
+                    })
+                    .map_or(uri.len(), |x| x + LOCAL_URI_SCHEME.len());
+
+                if is_value {
+                    paths.extend(decode_local_uri(&uri[..length]));
+                }
+                rest = &uri[length..];
+            }
+        }
+
+        let mut paths = Vec::new();
+        match self {
+            Self::InMemory(body) => find_in(body, &mut paths),
+            Self::Streamed(segments) => {
+                for segment in segments {
+                    if let Segment::Bytes(bytes) = segment {
+                        find_in(bytes, &mut paths);
+                    }
+                }
+            }
+        }
+
+        paths
+    }
+
+    /// Finds the `chat_id` parameter in the body.
+    pub fn chat_id(&self, boundary: Option<&str>) -> Option<ChatId> {
+        if let Some(boundary) = boundary {
+            let chat_id = from_utf8(self.field(boundary, "chat_id")?).ok()?;
+
+            return Some(chat_id.parse().map_or_else(
+                |_| ChatId::Username(chat_id.to_owned()),
+                |id| ChatId::Id(chat::Id(id)),
+            ));
+        }
+
+        let body: serde_json::Value =
+            serde_json::from_slice(self.in_memory()?).ok()?;
+        match &body["chat_id"] {
+            serde_json::Value::Number(id) => {
+                Some(ChatId::Id(chat::Id(id.as_i64()?)))
+            }
+            serde_json::Value::String(username) => {
+                Some(ChatId::Username(username.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the body can be sent again, i.e. it doesn't stream a reader
+    /// which can only be read once.
+    pub fn is_replayable(&self) -> bool {
+        match self {
+            Self::InMemory(_) => true,
+            Self::Streamed(segments) => !segments.iter().any(|segment| {
+                matches!(segment, Segment::File(FileSource::Reader(_)))
+            }),
+        }
+    }
+
+    /// Rewrites the body with `rewrite`. For streamed bodies, `rewrite`
+    /// is applied to each in-memory segment until it succeeds.
+    pub fn rewrite<T>(
+        &self,
+        rewrite: impl Fn(&[u8]) -> Option<(T, Vec<u8>)>,
+    ) -> Option<(T, Self)> {
+        match self {
+            Self::InMemory(body) => rewrite(body)
+                .map(|(value, body)| (value, Self::InMemory(body.into()))),
+            Self::Streamed(segments) => {
+                let (index, value, rewritten) = segments
+                    .iter()
+                    .enumerate()
+                    .find_map(|(index, segment)| {
+                        if let Segment::Bytes(bytes) = segment {
+                            let (value, rewritten) = rewrite(bytes)?;
+                            Some((index, value, rewritten))
+                        } else {
+                            None
+                        }
+                    })?;
+
+                let mut segments = segments.clone();
+                segments[index] = Segment::Bytes(rewritten.into());
+                Some((value, Self::Streamed(segments)))
+            }
+        }
+    }
+
+    /// Turns the body into `hyper`'s body, opening the files to stream.
+    /// Returns the body's length along with it, and a future which tells
+    /// when the body has been sent.
+    pub async fn into_hyper(self) -> io::Result<(Body, u64, Uploaded)> {
+        let (sender, uploaded) = oneshot::channel();
+        let segments = match self {
+            Self::InMemory(body) => {
+                let length = body.len() as u64;
+                let _ = sender.send(());
+                return Ok((Body::from(body), length, uploaded));
+            }
+            Self::Streamed(segments) => segments,
+        };
+
+        let mut length = 0;
+        let mut streams = Vec::with_capacity(segments.len());
+
+        for segment in segments {
+            let stream = match segment {
+                Segment::Bytes(bytes) => {
+                    length += bytes.len() as u64;
+                    stream::once(future::ready(Ok(bytes))).boxed()
+                }
+                Segment::File(FileSource::Bytes(bytes)) => {
+                    length += bytes.len() as u64;
+                    stream::once(future::ready(Ok(bytes.into()))).boxed()
+                }
+                Segment::File(FileSource::Path(path)) => {
+                    let file = fs::File::open(path).await?;
+                    let file_length = file.metadata().await?.len();
+                    length += file_length;
+                    read_chunks(file.take(file_length))
+                }
+                Segment::File(FileSource::Reader(reader)) => {
+                    let source = reader.take().ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::Other,
+                            "the reader has already been read by a previous \
+                             attempt",
+                        )
+                    })?;
+                    length += reader.len;
+                    read_chunks(source.take(reader.len))
+                }
+            };
+            streams.push(stream);
+        }
+
+        let mut sender = Some(sender);
+        let end = stream::poll_fn(move |_| {
+            if let Some(sender) = sender.take() {
+                let _ = sender.send(());
+            }
+            Poll::Ready(None)
+        });
+        let body =
+            Body::wrap_stream(stream::iter(streams).flatten().chain(end));
+        Ok((body, length, uploaded))
+    }
+}
+
+/// Generates a boundary for a body which can't be checked for collisions
+/// because its files are streamed. The boundary is long and random enough
+/// to not appear in files by chance.
+fn random_boundary() -> String {
+    (0..4).fold(String::with_capacity(64), |mut boundary, index| {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(index);
+        let _ = write!(boundary, "{:016x}", hasher.finish());
+        boundary
+    })
 }
 
 pub struct Multipart<'a> {
@@ -37,28 +370,30 @@ impl<'a> Multipart<'a> {
         }
     }
 
-    pub fn str(mut self, name: &'static str, value: &'a str) -> Self {
-        self.parts.push(Part {
-            header: Header::Field(name),
-            body: Cow::Borrowed(value.as_bytes()),
-        });
-        self
+    pub fn str(self, name: &'static str, value: &'a str) -> Self {
+        self.part(Cow::Borrowed(name), Cow::Borrowed(value.as_bytes()))
     }
 
-    fn part(mut self, name: &'static str, body: Cow<'a, [u8]>) -> Self {
+    pub fn part(mut self, name: Cow<'a, str>, body: Cow<'a, [u8]>) -> Self {
         self.parts.push(Part {
             header: Header::Field(name),
-            body,
+            body: PartBody::InMemory(body),
         });
         self
     }
 
     pub fn string(self, name: &'static str, value: &impl ToString) -> Self {
-        self.part(name, Cow::Owned(value.to_string().into_bytes()))
+        self.part(
+            Cow::Borrowed(name),
+            Cow::Owned(value.to_string().into_bytes()),
+        )
     }
 
     pub fn json(self, name: &'static str, value: impl Serialize) -> Self {
-        self.part(name, Cow::Owned(serde_json::to_vec(&value).unwrap()))
+        self.part(
+            Cow::Borrowed(name),
+            Cow::Owned(serde_json::to_vec(&value).unwrap()),
+        )
     }
 
     pub fn maybe_str(self, name: &'static str, value: Option<&'a str>) -> Self {
@@ -101,11 +436,11 @@ impl<'a> Multipart<'a> {
         mut self,
         name: Cow<'a, str>,
         filename: &'a str,
-        body: &'a [u8],
+        body: PartBody<'a>,
     ) -> Self {
         self.parts.push(Part {
             header: Header::File { name, filename },
-            body: Cow::Borrowed(body),
+            body,
         });
         self
     }
@@ -116,25 +451,71 @@ impl<'a> Multipart<'a> {
         filename: &'a str,
         body: &'a [u8],
     ) -> Self {
+        let body = PartBody::InMemory(Cow::Borrowed(body));
         self.file_cow(Cow::Borrowed(name), filename, body)
     }
 
-    pub fn file_owned_name(
+    pub fn input_file(
+        self,
+        name: &'a str,
+        filename: &'a str,
+        source: &'a FileSource,
+    ) -> Self {
+        self.file_cow(Cow::Borrowed(name), filename, source.into())
+    }
+
+    pub fn input_file_owned_name(
         self,
         name: String,
         filename: &'a str,
-        body: &'a [u8],
+        source: &'a FileSource,
     ) -> Self {
-        self.file_cow(Cow::Owned(name), filename, body)
+        self.file_cow(Cow::Owned(name), filename, source.into())
+    }
+
+    pub fn finish(self) -> (String, RequestBody) {
+        if self.parts.iter().any(|part| part.body.is_streamed()) {
+            return self.finish_streamed();
+        }
+
+        let (boundary, body) = self.finish_in_memory();
+        (boundary, RequestBody::InMemory(body.into()))
+    }
+
+    fn finish_streamed(self) -> (String, RequestBody) {
+        let boundary = random_boundary();
+        let mut segments = Vec::new();
+        let mut buffer = Vec::new();
+
+        for part in self.parts {
+            part.write_header(&mut buffer, boundary.as_bytes());
+
+            match part.body {
+                PartBody::InMemory(bytes) => buffer.extend_from_slice(&bytes),
+                PartBody::Streamed(source) => {
+                    let bytes = mem::take(&mut buffer);
+                    segments.push(Segment::Bytes(bytes.into()));
+                    segments.push(Segment::File(source.clone()));
+                }
+            }
+            buffer.extend_from_slice(b"\r\n");
+        }
+
+        buffer.extend_from_slice(b"--");
+        buffer.extend_from_slice(boundary.as_bytes());
+        buffer.extend_from_slice(b"--\r\n");
+        segments.push(Segment::Bytes(buffer.into()));
+
+        (boundary, RequestBody::Streamed(segments))
     }
 
-    pub fn finish(self) -> (String, Vec<u8>) {
+    fn finish_in_memory(self) -> (String, Vec<u8>) {
         let mut line_lengths = HashSet::new();
 
         for part in &self.parts {
             let mut current_line_length = 0;
 
-            for line in part.body.split(|byte| *byte == b'\n') {
+            for line in part.body.bytes().split(|byte| *byte == b'\n') {
                 current_line_length += line.len();
 
                 if line.ends_with(b"\r") {
@@ -162,20 +543,8 @@ impl<'a> Multipart<'a> {
         let mut body = Vec::new();
 
         for part in self.parts {
-            body.extend_from_slice(b"--");
-            body.extend_from_slice(boundary);
-            body.extend_from_slice(b"\r\n");
-
-            body.extend_from_slice(b"Content-Disposition: form-data; ");
-            body.extend_from_slice(
-                part.header.content_disposition().as_bytes(),
-            );
-            body.extend_from_slice(b"\r\n\r\n");
-
-            match part.body {
-                Cow::Owned(bytes) => body.extend_from_slice(&bytes[..]),
-                Cow::Borrowed(bytes) => body.extend_from_slice(bytes),
-            }
+            part.write_header(&mut body, boundary);
+            body.extend_from_slice(part.body.bytes());
             body.extend_from_slice(b"\r\n");
         }
 
@@ -186,3 +555,149 @@ impl<'a> Multipart<'a> {
         (boundary_string, body)
     }
 }
+
+impl<'a> From<&'a FileSource> for PartBody<'a> {
+    fn from(source: &'a FileSource) -> Self {
+        match source {
+            FileSource::Bytes(bytes) => Self::InMemory(Cow::Borrowed(bytes)),
+            source => Self::Streamed(source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &[u8]) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "tbot-{}-{}",
+                std::process::id(),
+                name
+            ));
+            std::fs::write(&path, contents).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    async fn stream(body: RequestBody) -> (Vec<u8>, u64) {
+        let (body, length, _) = body.into_hyper().await.unwrap();
+        let body = hyper::body::to_bytes(body).await.unwrap();
+        (body.to_vec(), length)
+    }
+
+    fn expected_body(boundary: &str, chat_id: &str, file: &str) -> Vec<u8> {
+        format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"chat_id\"\r\n\r\n\
+             {chat_id}\r\n\
+             --{boundary}\r\n\
+             Content-Disposition: form-data; name=\"document\"; \
+             filename=\"file.txt\"\r\n\r\n\
+             {file}\r\n\
+             --{boundary}--\r\n",
+            boundary = boundary,
+            chat_id = chat_id,
+            file = file,
+        )
+        .into_bytes()
+    }
+
+    #[tokio::test]
+    async fn streams_files_from_paths() {
+        let file = TempFile::new("streamed.txt", b"file contents");
+        let source = FileSource::Path(file.0.clone());
+
+        let (boundary, body) = Multipart::new(2)
+            .str("chat_id", "1")
+            .input_file("document", "file.txt", &source)
+            .finish();
+
+        assert_eq!(boundary.len(), 64);
+        let segments = match &body {
+            RequestBody::Streamed(segments) => segments,
+            RequestBody::InMemory(_) => panic!("the body isn't streamed"),
+        };
+        assert!(matches!(
+            segments[..],
+            [
+                Segment::Bytes(_),
+                Segment::File(FileSource::Path(_)),
+                Segment::Bytes(_)
+            ]
+        ));
+
+        let expected = expected_body(&boundary, "1", "file contents");
+        let (streamed, length) = stream(body.clone()).await;
+        assert_eq!(streamed, expected);
+        assert_eq!(length, streamed.len() as u64);
+
+        // each attempt opens the file anew
+        let (streamed, length) = stream(body).await;
+        assert_eq!(streamed, expected);
+        assert_eq!(length, streamed.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn rewrites_streamed_bodies() {
+        let file = TempFile::new("rewritten.txt", b"file contents");
+        let source = FileSource::Path(file.0.clone());
+
+        let (boundary, body) = Multipart::new(2)
+            .str("chat_id", "1")
+            .input_file("document", "file.txt", &source)
+            .finish();
+
+        let (value, body) = body
+            .rewrite(|bytes| {
+                let value = b"\r\n\r\n1\r\n";
+                let position = bytes
+                    .windows(value.len())
+                    .position(|window| window == value)?
+                    + 4;
+                let mut rewritten = bytes.to_vec();
+                rewritten.splice(position..=position, b"42".iter().copied());
+                Some(((), rewritten))
+            })
+            .unwrap();
+        assert_eq!(value, ());
+
+        let (streamed, length) = stream(body).await;
+        assert_eq!(streamed, expected_body(&boundary, "42", "file contents"));
+        assert_eq!(length, streamed.len() as u64);
+    }
+
+    #[test]
+    fn finds_fields() {
+        let body = RequestBody::from(br#"{"chat_id":-1234}"#.to_vec());
+        assert_eq!(body.chat_id(None), Some(ChatId::Id(chat::Id(-1234))));
+
+        let body = RequestBody::from(br#"{"chat_id":"@tbot"}"#.to_vec());
+        let username = ChatId::Username(String::from("@tbot"));
+        assert_eq!(body.chat_id(None), Some(username.clone()));
+
+        let body = RequestBody::from(br#"{"text":"foo"}"#.to_vec());
+        assert_eq!(body.chat_id(None), None);
+
+        let source = FileSource::Path(PathBuf::from("file.txt"));
+        let (boundary, body) = Multipart::new(3)
+            .str("caption", "foo\r\nbar")
+            .input_file("document", "file.txt", &source)
+            .str("chat_id", "@tbot")
+            .finish();
+        assert!(matches!(body, RequestBody::Streamed(_)));
+        assert_eq!(body.chat_id(Some(&boundary)), Some(username));
+        assert_eq!(body.field(&boundary, "caption"), Some(&b"foo\r\nbar"[..]));
+        assert_eq!(body.field(&boundary, "document"), None);
+    }
+}
@@ -281,6 +281,15 @@ impl<S> Chats<S> {
     {
         self.chats.retain(|&id, state| predicate(id, state))
     }
+
+    /// Moves a chat's state from its old ID to its new one, e.g. when a group
+    /// is upgraded to a supergroup. If there's no state for the old ID,
+    /// the storage is left intact. If there's already state for the new ID,
+    /// the moved state replaces it, and the replaced state is returned.
+    pub fn migrate(&mut self, from: chat::Id, to: chat::Id) -> Option<S> {
+        let state = self.chats.remove(&from)?;
+        self.chats.insert(to, state)
+    }
 }
 
 impl<S> IntoIterator for Chats<S> {
@@ -368,3 +377,26 @@ impl<S> Default for Chats<S> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_chats() {
+        let mut chats = Chats::new();
+        chats.insert_by_id(chat::Id(-1), "old");
+        chats.insert_by_id(chat::Id(-100), "new");
+
+        assert_eq!(chats.migrate(chat::Id(-1), chat::Id(-100)), Some("new"));
+        assert_eq!(chats.get_by_id(chat::Id(-100)), Some(&"old"));
+        assert!(!chats.has_by_id(chat::Id(-1)));
+
+        assert_eq!(chats.migrate(chat::Id(-1), chat::Id(-100)), None);
+        assert_eq!(chats.get_by_id(chat::Id(-100)), Some(&"old"));
+
+        assert_eq!(chats.migrate(chat::Id(-100), chat::Id(-200)), None);
+        assert_eq!(chats.get_by_id(chat::Id(-200)), Some(&"old"));
+        assert_eq!(chats.len(), 1);
+    }
+}
@@ -418,6 +418,35 @@ impl<S> Messages<S> {
     {
         self.messages.retain(|&id, state| predicate(id, state))
     }
+
+    /// Moves state for all messages from a chat with its old ID to the chat's
+    /// new ID, e.g. when a group is upgraded to a supergroup. Message IDs
+    /// are left intact. If a message under the new ID already has state,
+    /// the moved state replaces it, and the replaced states are returned.
+    pub fn migrate(
+        &mut self,
+        from: chat::Id,
+        to: chat::Id,
+    ) -> Vec<(MessageId, S)> {
+        let ids: Vec<_> = self
+            .messages
+            .keys()
+            .filter(|id| id.chat_id == from)
+            .copied()
+            .collect();
+
+        let mut replaced = Vec::new();
+        for id in ids {
+            if let Some(state) = self.messages.remove(&id) {
+                let id = MessageId { chat_id: to, ..id };
+                if let Some(state) = self.messages.insert(id, state) {
+                    replaced.push((id, state));
+                }
+            }
+        }
+
+        replaced
+    }
 }
 
 impl<S> IntoIterator for Messages<S> {
@@ -505,3 +534,34 @@ impl<S> Default for Messages<S> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn id(chat_id: i64, message_id: u32) -> MessageId {
+        MessageId {
+            chat_id: chat::Id(chat_id),
+            message_id: message::Id(message_id),
+        }
+    }
+
+    #[test]
+    fn migrates_messages_to_the_new_chat() {
+        let mut messages = Messages::new();
+        messages.insert_by_id(id(-1, 1), "old first");
+        messages.insert_by_id(id(-1, 2), "old second");
+        messages.insert_by_id(id(-2, 1), "other chat");
+        messages.insert_by_id(id(-100, 2), "new second");
+        messages.insert_by_id(id(-100, 3), "new third");
+
+        let replaced = messages.migrate(chat::Id(-1), chat::Id(-100));
+
+        assert_eq!(replaced, [(id(-100, 2), "new second")]);
+        assert!(messages.is_empty_in_chat_by_id(chat::Id(-1)));
+        assert_eq!(messages.get_by_id(id(-100, 1)), Some(&"old first"));
+        assert_eq!(messages.get_by_id(id(-100, 2)), Some(&"old second"));
+        assert_eq!(messages.get_by_id(id(-100, 3)), Some(&"new third"));
+        assert_eq!(messages.get_by_id(id(-2, 1)), Some(&"other chat"));
+    }
+}
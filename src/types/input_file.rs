@@ -1,12 +1,22 @@
 //! Types representing uploadable media.
 
 use crate::types::file;
+use std::{
+    fmt::{self, Debug, Formatter, Write},
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    ptr,
+    sync::{Arc, Mutex},
+};
+use tokio::io::AsyncRead;
 
 mod animation;
+mod attachment;
 mod audio;
 mod chat_photo;
 mod document;
 mod editable_media;
+mod file_kind;
 mod media_group;
 mod photo;
 mod png_sticker;
@@ -19,13 +29,14 @@ mod video;
 mod video_note;
 mod voice;
 
-pub(crate) use media_group::{Album, AnyGroupMedia};
 pub use {
     animation::Animation,
+    attachment::Attachment,
     audio::Audio,
     chat_photo::ChatPhoto,
     document::Document,
     editable_media::EditableMedia,
+    file_kind::FileKind,
     media_group::{MediaGroup, PhotoOrVideo},
     photo::Photo,
     png_sticker::PngSticker,
@@ -38,12 +49,95 @@ pub use {
     video_note::VideoNote,
     voice::Voice,
 };
+pub(crate) use {
+    file_kind::detect as detect_kind,
+    media_group::{Album, AnyGroupMedia},
+};
+
+/// The prefix of local files' URIs.
+pub(crate) const LOCAL_URI_SCHEME: &[u8] = b"file://";
+
+/// Decodes the path from a `file://` URI of a local file.
+pub(crate) fn decode_local_uri(uri: &[u8]) -> Option<PathBuf> {
+    if !uri.starts_with(LOCAL_URI_SCHEME) {
+        return None;
+    }
+
+    let mut bytes = uri[LOCAL_URI_SCHEME.len()..].iter();
+    let mut path = Vec::with_capacity(uri.len());
+    while let Some(&byte) = bytes.next() {
+        if byte == b'%' {
+            let code = [*bytes.next()?, *bytes.next()?];
+            let code = std::str::from_utf8(&code).ok()?;
+            path.push(u8::from_str_radix(code, 16).ok()?);
+        } else {
+            path.push(byte);
+        }
+    }
+
+    String::from_utf8(path).ok().map(PathBuf::from)
+}
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub(crate) enum InputFile {
-    File { filename: String, bytes: Vec<u8> },
+    File {
+        filename: String,
+        source: FileSource,
+    },
     Url(String),
     Id(file::Id),
+    /// A `file://` URI of a file on the self-hosted Bot API server's machine.
+    Local(String),
+}
+
+/// Where the contents of a file to upload come from.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub(crate) enum FileSource {
+    Bytes(Vec<u8>),
+    /// The file is opened anew for each attempt to call a method.
+    Path(PathBuf),
+    /// The reader can only be read once, so a method call with it is never
+    /// retried.
+    Reader(Reader),
+}
+
+type BoxedReader = Box<dyn AsyncRead + Send + Unpin>;
+
+#[derive(Clone)]
+pub(crate) struct Reader {
+    reader: Arc<Mutex<Option<BoxedReader>>>,
+    pub(crate) len: u64,
+}
+
+impl Reader {
+    /// Takes the reader out, returning `None` if it has been taken before.
+    pub(crate) fn take(&self) -> Option<BoxedReader> {
+        self.reader.lock().unwrap().take()
+    }
+}
+
+impl Debug for Reader {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Reader")
+            .field("reader", &format_args!("_"))
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl PartialEq for Reader {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.reader, &other.reader)
+    }
+}
+
+impl Eq for Reader {}
+
+impl Hash for Reader {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(Arc::as_ptr(&self.reader), state);
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
@@ -53,6 +147,84 @@ struct WithName<'a> {
 }
 
 impl InputFile {
+    pub(crate) fn with_bytes(
+        filename: impl Into<String>,
+        bytes: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self::File {
+            filename: filename.into(),
+            source: FileSource::Bytes(bytes.into()),
+        }
+    }
+
+    /// Uses the file's name as the filename, falling back to `default_filename`
+    /// if the path doesn't have one.
+    pub(crate) fn with_path(
+        path: impl Into<PathBuf>,
+        default_filename: &str,
+    ) -> Self {
+        let path = path.into();
+        let filename = path.file_name().map_or_else(
+            || default_filename.into(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+
+        Self::File {
+            filename,
+            source: FileSource::Path(path),
+        }
+    }
+
+    pub(crate) fn with_reader(
+        filename: impl Into<String>,
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        len: u64,
+    ) -> Self {
+        Self::File {
+            filename: filename.into(),
+            source: FileSource::Reader(Reader {
+                reader: Arc::new(Mutex::new(Some(Box::new(reader)))),
+                len,
+            }),
+        }
+    }
+
+    /// Refers to a file on the machine where a self-hosted Bot API server
+    /// runs in the `--local` mode, which reads the file itself instead of
+    /// receiving an upload.
+    pub(crate) fn with_local_path(
+        path: impl Into<PathBuf>,
+        kind: &str,
+    ) -> Self {
+        let path = path.into();
+        assert!(
+            path.is_absolute(),
+            "\n[tbot]: {}'s local path must be absolute, got `{}`\n",
+            kind,
+            path.display(),
+        );
+
+        let path = path.to_str().unwrap_or_else(|| {
+            panic!("\n[tbot]: {}'s local path must be valid UTF-8\n", kind)
+        });
+
+        let uri =
+            path.bytes().fold(String::from("file://"), |mut uri, byte| {
+                if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+                    uri.push(char::from(byte));
+                } else {
+                    let _ = write!(uri, "%{:02X}", byte);
+                }
+                uri
+            });
+
+        Self::Local(uri)
+    }
+
+    pub(crate) const fn is_local(&self) -> bool {
+        matches!(self, Self::Local(..))
+    }
+
     fn serialize<S>(&self, serializer: S, name: &str) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
@@ -61,7 +233,7 @@ impl InputFile {
             Self::File { .. } => {
                 serializer.serialize_str(&format!("attach://{}", name))
             }
-            Self::Url(file) | Self::Id(file::Id(file)) => {
+            Self::Url(file) | Self::Id(file::Id(file)) | Self::Local(file) => {
                 serializer.serialize_str(file)
             }
         }
@@ -80,3 +252,23 @@ impl serde::Serialize for WithName<'_> {
         self.file.serialize(serializer, self.name)
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_encodes_local_paths() {
+        let file = InputFile::with_local_path("/srv/my files/#1?.mp4", "Video");
+        assert_eq!(
+            file,
+            InputFile::Local(String::from(
+                "file:///srv/my%20files/%231%3F.mp4"
+            ))
+        );
+        assert_eq!(
+            decode_local_uri(b"file:///srv/my%20files/%231%3F.mp4"),
+            Some(PathBuf::from("/srv/my files/#1?.mp4"))
+        );
+    }
+}
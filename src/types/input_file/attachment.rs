@@ -0,0 +1,53 @@
+use super::{
+    Animation, Audio, ChatPhoto, Document, InputFile, Photo, PngSticker,
+    Sticker, StickerSetThumb, TgsSticker, Thumb, Video, VideoNote, Voice,
+};
+
+/// Represents a file attached to a [`RawMethod`] call.
+///
+/// Only the file itself is attached. Settings such as captions are ignored,
+/// so pass them as parameters of the call, and attach thumbnails as
+/// separate [`Thumb`]s.
+///
+/// [`RawMethod`]: crate::methods::RawMethod
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[must_use]
+pub struct Attachment(pub(crate) InputFile);
+
+macro_rules! from_media {
+    ($($type:ty),+) => {
+        $(
+            impl From<$type> for Attachment {
+                fn from(file: $type) -> Self {
+                    Self(file.media)
+                }
+            }
+        )+
+    };
+}
+
+from_media!(
+    Animation,
+    Audio,
+    Document,
+    Photo,
+    PngSticker,
+    Sticker,
+    StickerSetThumb,
+    TgsSticker,
+    Video,
+    VideoNote,
+    Voice
+);
+
+impl From<ChatPhoto> for Attachment {
+    fn from(photo: ChatPhoto) -> Self {
+        Self(photo.0)
+    }
+}
+
+impl From<Thumb> for Attachment {
+    fn from(thumb: Thumb) -> Self {
+        Self(thumb.0)
+    }
+}
@@ -4,6 +4,8 @@ use crate::types::{
     parameters::{ParseMode, Text},
 };
 use serde::{ser::SerializeMap, Serialize, Serializer};
+use std::path::PathBuf;
+use tokio::io::AsyncRead;
 
 /// Represents an audio to be sent.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
@@ -19,7 +21,7 @@ pub struct Audio {
 }
 
 impl Audio {
-    const fn new(media: InputFile) -> Self {
+    pub(crate) const fn new(media: InputFile) -> Self {
         Self {
             media,
             thumb: None,
@@ -33,10 +35,42 @@ impl Audio {
 
     /// Constructs an `Audio` from bytes.
     pub fn with_bytes(bytes: impl Into<Vec<u8>>) -> Self {
-        Self::new(InputFile::File {
-            filename: "audio.mp3".into(),
-            bytes: bytes.into(),
-        })
+        Self::new(InputFile::with_bytes("audio.mp3", bytes))
+    }
+
+    /// Constructs an `Audio` from a file at `path`. The file is streamed
+    /// when uploading rather than read into memory.
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self::new(InputFile::with_path(path, "audio.mp3"))
+    }
+
+    /// Constructs an `Audio` from `reader`, which must yield exactly `len`
+    /// bytes. The reader is streamed when uploading, so the method call
+    /// isn't retried if it fails.
+    pub fn with_reader(
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        len: u64,
+    ) -> Self {
+        Self::new(InputFile::with_reader("audio.mp3", reader, len))
+    }
+
+    /// Constructs a `Audio` from a file on the machine where your self-hosted
+    /// Bot API server runs in the `--local` mode. The server reads the file
+    /// itself, so nothing is uploaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` isn't absolute or isn't valid UTF-8. `tbot` checks
+    /// that the file exists before calling a method, so the server must see
+    /// the same filesystem as the bot. Calling a method with such a file fails
+    /// with [`errors::MethodCall::LocalFile`] if the file doesn't exist or
+    /// the bot isn't configured to use a self-hosted server with
+    /// [`bot::Builder::server_uri`].
+    ///
+    /// [`errors::MethodCall::LocalFile`]: crate::errors::MethodCall::LocalFile
+    /// [`bot::Builder::server_uri`]: crate::bot::Builder::server_uri
+    pub fn with_local_path(path: impl Into<PathBuf>) -> Self {
+        Self::new(InputFile::with_local_path(path, "Audio"))
     }
 
     /// Constructs an `Audio` from a file ID.
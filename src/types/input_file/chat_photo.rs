@@ -1,5 +1,7 @@
 use super::InputFile;
 use serde::{Serialize, Serializer};
+use std::path::PathBuf;
+use tokio::io::AsyncRead;
 
 /// Represents a chat photo to be set.
 ///
@@ -11,12 +13,42 @@ pub struct ChatPhoto(pub(crate) InputFile);
 impl ChatPhoto {
     /// Constructs a `ChatPhoto`.
     pub fn with_bytes(bytes: impl Into<Vec<u8>>) -> Self {
-        let file = InputFile::File {
-            filename: "photo.jpg".into(),
-            bytes: bytes.into(),
-        };
+        Self(InputFile::with_bytes("photo.jpg", bytes))
+    }
+
+    /// Constructs a `ChatPhoto` from a file at `path`. The file is streamed
+    /// when uploading rather than read into memory.
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self(InputFile::with_path(path, "photo.jpg"))
+    }
+
+    /// Constructs a `ChatPhoto` from `reader`, which must yield exactly `len`
+    /// bytes. The reader is streamed when uploading, so the method call
+    /// isn't retried if it fails.
+    pub fn with_reader(
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        len: u64,
+    ) -> Self {
+        Self(InputFile::with_reader("photo.jpg", reader, len))
+    }
 
-        Self(file)
+    /// Constructs a `ChatPhoto` from a file on the machine where your self-hosted
+    /// Bot API server runs in the `--local` mode. The server reads the file
+    /// itself, so nothing is uploaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` isn't absolute or isn't valid UTF-8. `tbot` checks
+    /// that the file exists before calling a method, so the server must see
+    /// the same filesystem as the bot. Calling a method with such a file fails
+    /// with [`errors::MethodCall::LocalFile`] if the file doesn't exist or
+    /// the bot isn't configured to use a self-hosted server with
+    /// [`bot::Builder::server_uri`].
+    ///
+    /// [`errors::MethodCall::LocalFile`]: crate::errors::MethodCall::LocalFile
+    /// [`bot::Builder::server_uri`]: crate::bot::Builder::server_uri
+    pub fn with_local_path(path: impl Into<PathBuf>) -> Self {
+        Self(InputFile::with_local_path(path, "ChatPhoto"))
     }
 }
 
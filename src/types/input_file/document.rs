@@ -4,6 +4,8 @@ use crate::types::{
     parameters::{ParseMode, Text},
 };
 use serde::{ser::SerializeMap, Serializer};
+use std::path::PathBuf;
+use tokio::io::AsyncRead;
 
 /// Represents a document to be sent.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
@@ -17,7 +19,7 @@ pub struct Document {
 }
 
 impl Document {
-    const fn new(media: InputFile) -> Self {
+    pub(crate) const fn new(media: InputFile) -> Self {
         Self {
             media,
             thumb: None,
@@ -32,10 +34,44 @@ impl Document {
         filename: impl Into<String>,
         bytes: impl Into<Vec<u8>>,
     ) -> Self {
-        Self::new(InputFile::File {
-            filename: filename.into(),
-            bytes: bytes.into(),
-        })
+        Self::new(InputFile::with_bytes(filename, bytes))
+    }
+
+    /// Constructs a `Document` from a file at `path`, using the file's name
+    /// as the document's filename. The file is streamed when uploading rather
+    /// than read into memory.
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self::new(InputFile::with_path(path, "document"))
+    }
+
+    /// Constructs a `Document` from `reader`, which must yield exactly `len`
+    /// bytes. The reader is streamed when uploading, so the method call
+    /// isn't retried if it fails.
+    pub fn with_reader(
+        filename: impl Into<String>,
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        len: u64,
+    ) -> Self {
+        Self::new(InputFile::with_reader(filename, reader, len))
+    }
+
+    /// Constructs a `Document` from a file on the machine where your self-hosted
+    /// Bot API server runs in the `--local` mode. The server reads the file
+    /// itself, so nothing is uploaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` isn't absolute or isn't valid UTF-8. `tbot` checks
+    /// that the file exists before calling a method, so the server must see
+    /// the same filesystem as the bot. Calling a method with such a file fails
+    /// with [`errors::MethodCall::LocalFile`] if the file doesn't exist or
+    /// the bot isn't configured to use a self-hosted server with
+    /// [`bot::Builder::server_uri`].
+    ///
+    /// [`errors::MethodCall::LocalFile`]: crate::errors::MethodCall::LocalFile
+    /// [`bot::Builder::server_uri`]: crate::bot::Builder::server_uri
+    pub fn with_local_path(path: impl Into<PathBuf>) -> Self {
+        Self::new(InputFile::with_local_path(path, "Document"))
     }
 
     /// Constructs a `Document` from a file ID.
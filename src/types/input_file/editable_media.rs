@@ -30,6 +30,22 @@ impl EditableMedia {
             Self::Video(..) => "video",
         }
     }
+
+    pub(crate) fn has_local_files(&self) -> bool {
+        let (media, thumb) = match self {
+            Self::Animation(animation) => {
+                (&animation.media, animation.thumb.as_ref())
+            }
+            Self::Audio(audio) => (&audio.media, audio.thumb.as_ref()),
+            Self::Document(document) => {
+                (&document.media, document.thumb.as_ref())
+            }
+            Self::Photo(photo) => (&photo.media, None),
+            Self::Video(video) => (&video.media, video.thumb.as_ref()),
+        };
+
+        media.is_local() || thumb.map_or(false, |thumb| thumb.0.is_local())
+    }
 }
 
 impl From<Animation> for EditableMedia {
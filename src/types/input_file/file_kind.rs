@@ -0,0 +1,220 @@
+use is_macro::Is;
+use std::path::Path;
+
+/// The largest photo the Bot API accepts. Larger photos are sent
+/// as documents.
+const MAX_PHOTO_SIZE: usize = 10 * 1024 * 1024;
+
+/// Major brands of MP4, M4V and 3GP videos.
+const MP4_BRANDS: &[&[u8]] = &[
+    b"isom", b"iso2", b"iso4", b"iso5", b"iso6", b"mp41", b"mp42", b"avc1",
+    b"dash", b"MSNV", b"M4V ", b"M4VH", b"M4VP", b"3gp4", b"3gp5", b"3gp6",
+    b"3gp7", b"3g2a",
+];
+
+/// Represents the kind of media a file is sent as by [`Bot::send_file`].
+///
+/// [`Bot::send_file`]: crate::Bot::send_file
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Is)]
+#[non_exhaustive]
+pub enum FileKind {
+    /// The file is sent as a photo.
+    Photo,
+    /// The file is sent as a video.
+    Video,
+    /// The file is sent as an animation.
+    Animation,
+    /// The file is sent as an audio.
+    Audio,
+    /// The file is sent as a voice message.
+    Voice,
+    /// The file is sent as a document.
+    Document,
+}
+
+/// File formats which can be sent as media other than a document.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Format {
+    Jpeg,
+    Png,
+    Gif,
+    Mp4,
+    M4a,
+    Mp3,
+    OggOpus,
+}
+
+impl Format {
+    fn sniff(bytes: &[u8], extension: &str) -> Option<Self> {
+        if bytes.starts_with(b"\xFF\xD8\xFF") {
+            return Some(Self::Jpeg);
+        }
+        if bytes.starts_with(b"\x89PNG\r\n\x1A\n") {
+            return Some(Self::Png);
+        }
+        if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            return Some(Self::Gif);
+        }
+        if bytes.get(4..8) == Some(b"ftyp") {
+            // other brands, e.g. HEIC or AVIF images, are sent as documents
+            return match bytes.get(8..12)? {
+                b"M4A " | b"M4B " => Some(Self::M4a),
+                brand if MP4_BRANDS.contains(&brand) => {
+                    if extension == "m4a" {
+                        Some(Self::M4a)
+                    } else {
+                        Some(Self::Mp4)
+                    }
+                }
+                _ => None,
+            };
+        }
+        if bytes.starts_with(b"OggS") {
+            return if bytes.get(28..36) == Some(b"OpusHead") {
+                Some(Self::OggOpus)
+            } else {
+                None
+            };
+        }
+        if bytes.starts_with(b"ID3") {
+            return Some(Self::Mp3);
+        }
+
+        // MPEG audio frames without an ID3 tag only start with a sync word,
+        // which is too weak a signal by itself.
+        let is_mpeg_frame =
+            matches!(bytes, [0xFF, second, ..] if second & 0xE0 == 0xE0);
+        if is_mpeg_frame && extension == "mp3" {
+            return Some(Self::Mp3);
+        }
+
+        None
+    }
+
+    const fn kind(self) -> FileKind {
+        match self {
+            Self::Jpeg | Self::Png => FileKind::Photo,
+            Self::Gif => FileKind::Animation,
+            Self::Mp4 => FileKind::Video,
+            Self::M4a | Self::Mp3 => FileKind::Audio,
+            Self::OggOpus => FileKind::Voice,
+        }
+    }
+
+    /// Extensions which make Telegram infer the format's MIME type,
+    /// the first one being preferred.
+    const fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Jpeg => &["jpg", "jpeg"],
+            Self::Png => &["png"],
+            Self::Gif => &["gif"],
+            Self::Mp4 => &["mp4", "m4v", "3gp"],
+            Self::M4a => &["m4a"],
+            Self::Mp3 => &["mp3"],
+            Self::OggOpus => &["ogg", "oga", "opus"],
+        }
+    }
+}
+
+/// Detects how to send a file by its contents and its name. Returns the kind
+/// along with the filename, which gets the format's extension if it doesn't
+/// have a suitable one already.
+pub fn detect(bytes: &[u8], filename: String) -> (FileKind, String) {
+    let extension = Path::new(&filename)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    let format = match Format::sniff(bytes, &extension) {
+        Some(format) => format,
+        None => return (FileKind::Document, filename),
+    };
+
+    let kind = match format.kind() {
+        FileKind::Photo if bytes.len() > MAX_PHOTO_SIZE => FileKind::Document,
+        kind => kind,
+    };
+
+    let extensions = format.extensions();
+    let filename = if extensions.contains(&extension.as_str()) {
+        filename
+    } else {
+        format!("{}.{}", filename, extensions[0])
+    };
+
+    (kind, filename)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(bytes: &[u8], filename: &str) -> (FileKind, String) {
+        super::detect(bytes, filename.to_owned())
+    }
+
+    #[test]
+    fn detects_media_by_magic_bytes() {
+        let jpeg = b"\xFF\xD8\xFF\xE0\0\x10JFIF";
+        assert_eq!(
+            detect(jpeg, "cat.jpeg"),
+            (FileKind::Photo, "cat.jpeg".into())
+        );
+        assert_eq!(detect(jpeg, "cat"), (FileKind::Photo, "cat.jpg".into()));
+        assert_eq!(
+            detect(jpeg, "cat.bin"),
+            (FileKind::Photo, "cat.bin.jpg".into())
+        );
+
+        let gif = b"GIF89a\x01\0\x01\0";
+        assert_eq!(
+            detect(gif, "dance.gif"),
+            (FileKind::Animation, "dance.gif".into())
+        );
+
+        let mp4 = b"\0\0\0\x20ftypisom\0\0\x02\0";
+        assert_eq!(
+            detect(mp4, "clip.mp4"),
+            (FileKind::Video, "clip.mp4".into())
+        );
+        let m4a = b"\0\0\0\x20ftypM4A \0\0\x02\0";
+        assert_eq!(detect(m4a, "song"), (FileKind::Audio, "song.m4a".into()));
+        let heic = b"\0\0\0\x18ftypheic\0\0\0\0";
+        assert_eq!(
+            detect(heic, "photo.heic"),
+            (FileKind::Document, "photo.heic".into())
+        );
+
+        let mut ogg = b"OggS".to_vec();
+        ogg.resize(28, 0);
+        ogg.extend_from_slice(b"OpusHead");
+        assert_eq!(
+            detect(&ogg, "note.ogg"),
+            (FileKind::Voice, "note.ogg".into())
+        );
+
+        let mp3 = b"\xFF\xFB\x90\x64";
+        assert_eq!(
+            detect(mp3, "song.mp3"),
+            (FileKind::Audio, "song.mp3".into())
+        );
+        assert_eq!(detect(mp3, "song"), (FileKind::Document, "song".into()));
+
+        assert_eq!(
+            detect(b"%PDF-1.7", "paper.pdf"),
+            (FileKind::Document, "paper.pdf".into())
+        );
+    }
+
+    #[test]
+    fn sends_large_photos_as_documents() {
+        let mut jpeg = b"\xFF\xD8\xFF\xE0".to_vec();
+        jpeg.resize(MAX_PHOTO_SIZE + 1, 0);
+
+        assert_eq!(
+            detect(&jpeg, "huge.jpg"),
+            (FileKind::Document, "huge.jpg".into())
+        );
+    }
+}
@@ -43,10 +43,23 @@ struct WithIndex<'a> {
     index: usize,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-pub struct Album(pub MediaGroup);
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Album<'a>(pub &'a MediaGroup);
 
 impl AnyGroupMedia<'_> {
+    pub(crate) fn has_local_files(&self) -> bool {
+        let (media, thumb) = match self {
+            Self::Photo(photo) => (&photo.media, None),
+            Self::Video(video) => (&video.media, video.thumb.as_ref()),
+            Self::Audio(audio) => (&audio.media, audio.thumb.as_ref()),
+            Self::Document(document) => {
+                (&document.media, document.thumb.as_ref())
+            }
+        };
+
+        media.is_local() || thumb.map_or(false, |thumb| thumb.0.is_local())
+    }
+
     fn serialize<S>(
         &self,
         serializer: S,
@@ -160,7 +173,7 @@ impl From<Vec<Document>> for MediaGroup {
     }
 }
 
-impl Serialize for Album {
+impl Serialize for Album<'_> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
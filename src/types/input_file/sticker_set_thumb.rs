@@ -1,5 +1,7 @@
 use super::InputFile;
 use crate::types::file;
+use std::path::PathBuf;
+use tokio::io::AsyncRead;
 
 /// Represents a sticker set thumb to be sent.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
@@ -15,18 +17,64 @@ impl StickerSetThumb {
 
     /// Constructs a `StickerSetThumb` from bytes of `.png` image.
     pub fn png_bytes(bytes: impl Into<Vec<u8>>) -> Self {
-        Self::new(InputFile::File {
-            filename: "thumb.png".into(),
-            bytes: bytes.into(),
-        })
+        Self::new(InputFile::with_bytes("thumb.png", bytes))
+    }
+
+    /// Constructs a `StickerSetThumb` from a `.png` image at `path`. The file
+    /// is streamed when uploading rather than read into memory.
+    pub fn png_path(path: impl Into<PathBuf>) -> Self {
+        Self::new(InputFile::with_path(path, "thumb.png"))
+    }
+
+    /// Constructs a `StickerSetThumb` from a `.png` image read from `reader`,
+    /// which must yield exactly `len` bytes. The reader is streamed when
+    /// uploading, so the method call isn't retried if it fails.
+    pub fn png_reader(
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        len: u64,
+    ) -> Self {
+        Self::new(InputFile::with_reader("thumb.png", reader, len))
     }
 
     /// Constructs a `StickerSetThumb` from bytes of `.tgs` animation.
     pub fn tgs_bytes(bytes: impl Into<Vec<u8>>) -> Self {
-        Self::new(InputFile::File {
-            filename: "thumb.tgs".into(),
-            bytes: bytes.into(),
-        })
+        Self::new(InputFile::with_bytes("thumb.tgs", bytes))
+    }
+
+    /// Constructs a `StickerSetThumb` from a `.tgs` animation at `path`. The file
+    /// is streamed when uploading rather than read into memory.
+    pub fn tgs_path(path: impl Into<PathBuf>) -> Self {
+        Self::new(InputFile::with_path(path, "thumb.tgs"))
+    }
+
+    /// Constructs a `StickerSetThumb` from a `.tgs` animation read from `reader`,
+    /// which must yield exactly `len` bytes. The reader is streamed when
+    /// uploading, so the method call isn't retried if it fails.
+    pub fn tgs_reader(
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        len: u64,
+    ) -> Self {
+        Self::new(InputFile::with_reader("thumb.tgs", reader, len))
+    }
+
+    /// Constructs a `StickerSetThumb` from a `.png` image or a `.tgs`
+    /// animation on the machine where your self-hosted Bot API server runs
+    /// in the `--local` mode. The server reads the file itself, so nothing
+    /// is uploaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` isn't absolute or isn't valid UTF-8. `tbot` checks
+    /// that the file exists before calling a method, so the server must see
+    /// the same filesystem as the bot. Calling a method with such a file fails
+    /// with [`errors::MethodCall::LocalFile`] if the file doesn't exist or
+    /// the bot isn't configured to use a self-hosted server with
+    /// [`bot::Builder::server_uri`].
+    ///
+    /// [`errors::MethodCall::LocalFile`]: crate::errors::MethodCall::LocalFile
+    /// [`bot::Builder::server_uri`]: crate::bot::Builder::server_uri
+    pub fn with_local_path(path: impl Into<PathBuf>) -> Self {
+        Self::new(InputFile::with_local_path(path, "StickerSetThumb"))
     }
 
     /// Constructs a `StickerSetThumb` from a file ID.
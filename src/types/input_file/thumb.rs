@@ -1,5 +1,7 @@
 use super::{InputFile, WithName};
 use serde::{ser::Serializer, Serialize};
+use std::path::PathBuf;
+use tokio::io::AsyncRead;
 
 /// Represents a thumb to be sent.
 ///
@@ -11,10 +13,42 @@ pub struct Thumb(pub(crate) InputFile);
 impl Thumb {
     /// Constructs a `Thumb`.
     pub fn with_bytes(bytes: impl Into<Vec<u8>>) -> Self {
-        Self(InputFile::File {
-            filename: "thumb.jpg".into(),
-            bytes: bytes.into(),
-        })
+        Self(InputFile::with_bytes("thumb.jpg", bytes))
+    }
+
+    /// Constructs a `Thumb` from a file at `path`. The file is streamed
+    /// when uploading rather than read into memory.
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self(InputFile::with_path(path, "thumb.jpg"))
+    }
+
+    /// Constructs a `Thumb` from `reader`, which must yield exactly `len`
+    /// bytes. The reader is streamed when uploading, so the method call
+    /// isn't retried if it fails.
+    pub fn with_reader(
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        len: u64,
+    ) -> Self {
+        Self(InputFile::with_reader("thumb.jpg", reader, len))
+    }
+
+    /// Constructs a `Thumb` from a file on the machine where your self-hosted
+    /// Bot API server runs in the `--local` mode. The server reads the file
+    /// itself, so nothing is uploaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` isn't absolute or isn't valid UTF-8. `tbot` checks
+    /// that the file exists before calling a method, so the server must see
+    /// the same filesystem as the bot. Calling a method with such a file fails
+    /// with [`errors::MethodCall::LocalFile`] if the file doesn't exist or
+    /// the bot isn't configured to use a self-hosted server with
+    /// [`bot::Builder::server_uri`].
+    ///
+    /// [`errors::MethodCall::LocalFile`]: crate::errors::MethodCall::LocalFile
+    /// [`bot::Builder::server_uri`]: crate::bot::Builder::server_uri
+    pub fn with_local_path(path: impl Into<PathBuf>) -> Self {
+        Self(InputFile::with_local_path(path, "Thumb"))
     }
 
     pub(super) const fn with_name<'a>(&'a self, name: &'a str) -> WithName<'a> {
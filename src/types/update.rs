@@ -56,6 +56,28 @@ pub enum Kind {
     Unknown,
 }
 
+impl Kind {
+    /// Returns the name of the update's field in the Bot API.
+    pub(crate) const fn name(&self) -> &'static str {
+        match self {
+            Self::Message(..) => "message",
+            Self::EditedMessage(..) => "edited_message",
+            Self::ChannelPost(..) => "channel_post",
+            Self::EditedChannelPost(..) => "edited_channel_post",
+            Self::InlineQuery(..) => "inline_query",
+            Self::CallbackQuery(..) => "callback_query",
+            Self::Poll(..) => "poll",
+            Self::PollAnswer(..) => "poll_answer",
+            Self::ChosenInlineResult(..) => "chosen_inline_result",
+            Self::ShippingQuery(..) => "shipping_query",
+            Self::PreCheckoutQuery(..) => "pre_checkout_query",
+            Self::MyChatMember(..) => "my_chat_member",
+            Self::ChatMember(..) => "chat_member",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
 /// Represents an update from Telegram.
 #[derive(Debug, Clone)]
 #[non_exhaustive]
@@ -1,5 +1,6 @@
 //! A few useful utilities.
 
+pub mod broadcast;
 mod chat_action_loop;
 pub mod entities;
 
@@ -0,0 +1,525 @@
+//! Sending the same message to many chats.
+//!
+//! Start a broadcast with [`Broadcast::new`], passing the chats to send
+//! the message to and a [`Template`] of the message. Running the broadcast
+//! produces a stream of [`Report`]s, one per chat in the order the chats
+//! were passed, so that you can show progress and prune chats which are
+//! no longer reachable:
+//!
+//! ```no_run
+//! # async fn foo(subscribers: Vec<tbot::types::chat::Id>) {
+//! use futures::StreamExt;
+//! use tbot::util::broadcast::{Broadcast, Outcome, Template};
+//!
+//! let bot = tbot::from_env!("BOT_TOKEN");
+//! let template = Template::text("`tbot` got a new release!");
+//! let mut reports = Broadcast::new(&bot, subscribers, template).run();
+//!
+//! while let Some(report) = reports.next().await {
+//!     if report.outcome.is_dead_chat() {
+//!         // remove `report.chat_id` from subscribers
+//!     } else if let Outcome::Migrated { to, .. } = report.outcome {
+//!         // replace `report.chat_id` with `to` in subscribers
+//!     }
+//!     // persist `report.checkpoint` to resume the broadcast after a restart
+//! }
+//! # }
+//! ```
+//!
+//! If the broadcast is interrupted, e.g. because the bot restarted, pass
+//! the last checkpoint you saw to [`Broadcast::resume_from`] along with
+//! the same chats to continue where the broadcast stopped. Note that
+//! the message is sent to several chats at the same time, so up to
+//! [`concurrency`] chats after the last checkpoint may have already received
+//! the message, and the resumed broadcast sends it to them again. In other
+//! words, the message is delivered at least once, not exactly once.
+//!
+//! [`concurrency`]: Broadcast::concurrency
+
+use crate::{
+    bot::{RateLimitedChat, RateLimiter, RateLimits},
+    errors::{self, RequestErrorKind},
+    types::{
+        chat, message,
+        parameters::{ChatId, ImplicitChatId, Text},
+    },
+    Bot,
+};
+use futures::stream::{self, Stream, StreamExt};
+use is_macro::Is;
+use std::{sync::Arc, time::Duration};
+use tokio::time::sleep;
+
+/// The message to broadcast.
+#[derive(Debug, Clone)]
+pub enum Template {
+    /// Sends a text message.
+    Text(Text),
+    /// Copies an existing message, which allows broadcasting any kind
+    /// of message, e.g. a photo with a caption.
+    Copy {
+        /// The chat where the message to copy is.
+        from_chat_id: ChatId,
+        /// The ID of the message to copy.
+        message_id: message::Id,
+    },
+}
+
+impl Template {
+    /// Constructs a template which sends a text message.
+    pub fn text(text: impl Into<Text>) -> Self {
+        Self::Text(text.into())
+    }
+
+    /// Constructs a template which copies an existing message.
+    pub fn copy(
+        from_chat_id: impl ImplicitChatId,
+        message_id: message::Id,
+    ) -> Self {
+        Self::Copy {
+            from_chat_id: from_chat_id.into(),
+            message_id,
+        }
+    }
+}
+
+/// How sending the message to a chat ended.
+#[derive(Debug, Is)]
+pub enum Outcome {
+    /// The message was sent with the following ID.
+    Sent(message::Id),
+    /// The user blocked the bot, or the bot was kicked from the chat.
+    Blocked,
+    /// The user deleted their account.
+    Deactivated,
+    /// The chat doesn't exist or the bot has no access to it.
+    ChatNotFound,
+    /// The group was upgraded to a supergroup, so the message was sent
+    /// to the supergroup instead.
+    Migrated {
+        /// The ID of the supergroup, which should replace the group's ID
+        /// in the list of subscribers.
+        to: chat::Id,
+        /// How sending the message to the supergroup ended.
+        outcome: Box<Self>,
+    },
+    /// Sending failed because of another error.
+    Failed(errors::MethodCall),
+}
+
+impl Outcome {
+    /// Checks if the bot can't reach the chat anymore, so that it should be
+    /// removed from the list of subscribers.
+    #[must_use]
+    pub const fn is_dead_chat(&self) -> bool {
+        matches!(self, Self::Blocked | Self::Deactivated | Self::ChatNotFound)
+    }
+
+    fn from_result(result: Result<message::Id, errors::MethodCall>) -> Self {
+        let error = match result {
+            Ok(id) => return Self::Sent(id),
+            Err(error) => error,
+        };
+
+        match error.request_error_kind() {
+            Some(RequestErrorKind::BotBlocked)
+            | Some(RequestErrorKind::BotKicked) => Self::Blocked,
+            Some(RequestErrorKind::UserDeactivated) => Self::Deactivated,
+            Some(RequestErrorKind::ChatNotFound) => Self::ChatNotFound,
+            _ => Self::Failed(error),
+        }
+    }
+}
+
+/// How many chats from the start of the list a broadcast has processed.
+///
+/// Pass it to [`Broadcast::resume_from`] to continue an interrupted broadcast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Checkpoint(pub usize);
+
+/// The result of sending the message to a chat.
+#[derive(Debug)]
+pub struct Report {
+    /// The chat the message was sent to.
+    pub chat_id: ChatId,
+    /// How sending the message ended.
+    pub outcome: Outcome,
+    /// The checkpoint to resume the broadcast from after this chat.
+    pub checkpoint: Checkpoint,
+}
+
+/// Sends the same message to many chats. See [the module's docs] for
+/// details.
+///
+/// [the module's docs]: self
+#[derive(Debug)]
+#[must_use = "a broadcast does nothing unless run"]
+pub struct Broadcast<I> {
+    bot: Bot,
+    chats: I,
+    template: Template,
+    checkpoint: Checkpoint,
+    concurrency: usize,
+    rate_limits: RateLimits,
+    max_attempts: u32,
+}
+
+impl<I, C> Broadcast<I>
+where
+    I: Iterator<Item = C> + Send + 'static,
+    C: ImplicitChatId,
+{
+    /// Starts configuring a broadcast of `template` to `chats`.
+    pub fn new(
+        bot: &Bot,
+        chats: impl IntoIterator<IntoIter = I>,
+        template: Template,
+    ) -> Self {
+        Self {
+            bot: bot.clone(),
+            chats: chats.into_iter(),
+            template,
+            checkpoint: Checkpoint(0),
+            concurrency: 10,
+            rate_limits: RateLimits::new(),
+            max_attempts: 5,
+        }
+    }
+
+    /// Configures the broadcast to skip the chats processed before
+    /// `checkpoint`. The chats must be passed in the same order as before.
+    ///
+    /// Chats after the checkpoint may have already received the message
+    /// if it was being sent to them when the broadcast was interrupted,
+    /// and they receive it again. See [the module's docs] for details.
+    ///
+    /// [the module's docs]: self
+    pub const fn resume_from(mut self, checkpoint: Checkpoint) -> Self {
+        self.checkpoint = checkpoint;
+        self
+    }
+
+    /// Configures how many messages may be sent at the same time. `10`
+    /// by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `concurrency` is `0`.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        assert!(concurrency > 0, "[tbot] Concurrency must not be zero");
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Configures how often the broadcast may send messages. By default,
+    /// the broadcast follows the Bot API's limits. If the bot has rate limits
+    /// configured with [`bot::Builder::rate_limits`], the broadcast follows
+    /// the bot's limits instead, and these limits don't apply.
+    ///
+    /// [`bot::Builder::rate_limits`]: crate::bot::Builder::rate_limits
+    pub const fn rate_limits(mut self, limits: RateLimits) -> Self {
+        self.rate_limits = limits;
+        self
+    }
+
+    /// Configures how many times the broadcast tries to send the message
+    /// to a chat when the Bot API asks to retry later. `5` by default.
+    pub const fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Runs the broadcast, reporting the result for each chat in the order
+    /// the chats were passed.
+    pub fn run(self) -> impl Stream<Item = Report> + Send + 'static {
+        let Self {
+            bot,
+            chats,
+            template,
+            checkpoint: Checkpoint(start),
+            concurrency,
+            rate_limits,
+            max_attempts,
+        } = self;
+
+        let limiter = if bot.has_rate_limits() {
+            None
+        } else {
+            Some(RateLimiter::new(rate_limits))
+        };
+        let sender = Arc::new(Sender {
+            bot,
+            template,
+            limiter,
+            max_attempts,
+        });
+
+        let chats = chats.skip(start).enumerate();
+        stream::iter(chats)
+            .map(move |(index, chat_id)| {
+                let sender = Arc::clone(&sender);
+                let chat_id: ChatId = chat_id.into();
+
+                async move {
+                    let outcome = sender.send(&chat_id).await;
+                    Report {
+                        chat_id,
+                        outcome,
+                        checkpoint: Checkpoint(start + index + 1),
+                    }
+                }
+            })
+            .buffered(concurrency)
+    }
+}
+
+#[derive(Debug)]
+struct Sender {
+    bot: Bot,
+    template: Template,
+    limiter: Option<RateLimiter>,
+    max_attempts: u32,
+}
+
+impl Sender {
+    async fn send(&self, chat_id: &ChatId) -> Outcome {
+        let result = self.send_with_retries(chat_id).await;
+        let to = match &result {
+            Err(errors::MethodCall::RequestError {
+                migrate_to_chat_id: Some(to),
+                ..
+            }) if self.can_migrate(chat_id) => *to,
+            _ => return Outcome::from_result(result),
+        };
+
+        let result = self.send_with_retries(&ChatId::Id(to)).await;
+        Outcome::Migrated {
+            to,
+            outcome: Box::new(Outcome::from_result(result)),
+        }
+    }
+
+    /// Checks if a migration error must be caused by `chat_id`. Only basic
+    /// groups migrate, and if the copied message is in a basic group too,
+    /// it's unknown which of the chats migrated.
+    fn can_migrate(&self, chat_id: &ChatId) -> bool {
+        let is_basic_group = |chat_id: &ChatId| {
+            matches!(
+                chat_id,
+                ChatId::Id(chat::Id(id)) if (-999_999_999_999..0).contains(id)
+            )
+        };
+
+        is_basic_group(chat_id)
+            && !matches!(
+                &self.template,
+                Template::Copy { from_chat_id, .. }
+                    if is_basic_group(from_chat_id)
+            )
+    }
+
+    async fn send_with_retries(
+        &self,
+        chat_id: &ChatId,
+    ) -> Result<message::Id, errors::MethodCall> {
+        let mut attempt = 1;
+
+        loop {
+            if let Some(limiter) = &self.limiter {
+                limiter.wait(RateLimitedChat::from(chat_id)).await;
+            }
+
+            let result = self.send_once(chat_id).await;
+            let retry_after = match &result {
+                Err(errors::MethodCall::RequestError {
+                    retry_after: Some(retry_after),
+                    ..
+                }) if attempt < self.max_attempts => *retry_after,
+                _ => return result,
+            };
+
+            sleep(Duration::from_secs(retry_after)).await;
+            attempt += 1;
+        }
+    }
+
+    async fn send_once(
+        &self,
+        chat_id: &ChatId,
+    ) -> Result<message::Id, errors::MethodCall> {
+        match &self.template {
+            Template::Text(text) => self
+                .bot
+                .send_message(chat_id.clone(), text.clone())
+                .without_retries()
+                .call()
+                .await
+                .map(|message| message.id),
+            Template::Copy {
+                from_chat_id,
+                message_id,
+            } => {
+                self.bot
+                    .copy_message(
+                        chat_id.clone(),
+                        from_chat_id.clone(),
+                        *message_id,
+                    )
+                    .without_retries()
+                    .call()
+                    .await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connectors::{Transport, TransportError};
+    use futures::future::BoxFuture;
+    use hyper::{Body, Request, Response};
+    use serde_json::{json, Value};
+    use std::{
+        num::NonZeroU32,
+        sync::{Arc, Mutex},
+    };
+
+    /// Records the chats messages are sent to and responds depending
+    /// on the chat.
+    #[derive(Debug, Default)]
+    struct Recording(Arc<Mutex<Vec<i64>>>);
+
+    impl Transport for Recording {
+        fn request(
+            &self,
+            request: Request<Body>,
+        ) -> BoxFuture<'static, Result<Response<Body>, TransportError>>
+        {
+            let requests = Arc::clone(&self.0);
+            Box::pin(async move {
+                let body = hyper::body::to_bytes(request.into_body()).await?;
+                let body: Value = serde_json::from_slice(&body)?;
+                let chat_id = body["chat_id"].as_i64().unwrap();
+
+                let mut requests = requests.lock().unwrap();
+                let attempts =
+                    requests.iter().filter(|&&id| id == chat_id).count();
+                requests.push(chat_id);
+
+                let response = match chat_id {
+                    2 => json!({
+                        "ok": false,
+                        "error_code": 403,
+                        "description": "Forbidden: bot was blocked by the user",
+                    }),
+                    -3 => json!({
+                        "ok": false,
+                        "error_code": 400,
+                        "description": "Bad Request: group chat was upgraded to a supergroup chat",
+                        "parameters": { "migrate_to_chat_id": -1_003 },
+                    }),
+                    4 if attempts == 0 => json!({
+                        "ok": false,
+                        "error_code": 429,
+                        "description": "Too Many Requests: retry after 0",
+                        "parameters": { "retry_after": 0 },
+                    }),
+                    _ => json!({
+                        "ok": true,
+                        "result": {
+                            "message_id": 10 + attempts,
+                            "date": 0,
+                            "chat": if chat_id > 0 {
+                                json!({
+                                    "id": chat_id,
+                                    "type": "private",
+                                    "first_name": "tbot",
+                                })
+                            } else {
+                                json!({
+                                    "id": chat_id,
+                                    "type": "supergroup",
+                                    "title": "tbot",
+                                })
+                            },
+                            "text": "foo",
+                        },
+                    }),
+                };
+
+                Ok(Response::new(Body::from(response.to_string())))
+            })
+        }
+    }
+
+    fn broadcast(
+        requests: &Arc<Mutex<Vec<i64>>>,
+        chats: Vec<i64>,
+    ) -> Broadcast<impl Iterator<Item = chat::Id> + Send + 'static> {
+        let bot = crate::bot::Builder::with_string_token(String::from("TOKEN"))
+            .transport(Recording(Arc::clone(requests)))
+            .build();
+        let limit = NonZeroU32::new(1000).unwrap();
+        let limits = RateLimits::new()
+            .private_chat(limit, Duration::from_secs(1))
+            .group(limit, Duration::from_secs(1));
+
+        Broadcast::new(
+            &bot,
+            chats.into_iter().map(chat::Id),
+            Template::text("foo"),
+        )
+        .rate_limits(limits)
+    }
+
+    #[tokio::test]
+    async fn reports_outcomes_in_order() {
+        let requests = Arc::default();
+        let reports: Vec<_> = broadcast(&requests, vec![1, 2, -3, 4])
+            .run()
+            .collect()
+            .await;
+
+        let chats: Vec<_> = reports
+            .iter()
+            .map(|report| report.chat_id.clone())
+            .collect();
+        let expected: Vec<_> =
+            vec![1, 2, -3, 4].into_iter().map(ChatId::from).collect();
+        assert_eq!(chats, expected);
+        let checkpoints: Vec<_> =
+            reports.iter().map(|report| report.checkpoint.0).collect();
+        assert_eq!(checkpoints, [1, 2, 3, 4]);
+
+        assert!(matches!(reports[0].outcome, Outcome::Sent(message::Id(10))));
+        assert!(reports[1].outcome.is_blocked());
+        match &reports[2].outcome {
+            Outcome::Migrated { to, outcome } => {
+                assert_eq!(*to, chat::Id(-1_003));
+                assert!(matches!(**outcome, Outcome::Sent(message::Id(10))));
+            }
+            outcome => panic!("unexpected outcome: {:?}", outcome),
+        }
+        assert!(matches!(reports[3].outcome, Outcome::Sent(message::Id(11))));
+
+        let mut requests = requests.lock().unwrap().clone();
+        requests.sort_unstable();
+        assert_eq!(requests, [-1_003, -3, 1, 2, 4, 4]);
+    }
+
+    #[tokio::test]
+    async fn resumes_from_checkpoints() {
+        let requests = Arc::default();
+        let reports: Vec<_> = broadcast(&requests, vec![1, 2, 4])
+            .resume_from(Checkpoint(2))
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].chat_id, ChatId::from(4));
+        assert_eq!(reports[0].checkpoint, Checkpoint(3));
+        assert_eq!(*requests.lock().unwrap(), [4, 4]);
+    }
+}